@@ -0,0 +1,228 @@
+//! `#[derive(AocParse)]`, which writes a `FromStr` for a struct or enum from
+//! a template of the line it is parsed from. Use it through `aoc::AocParse`.
+//!
+//! ```ignore
+//! #[derive(AocParse)]
+//! #[aoc(format = "move {count} from {from} to {to}")]
+//! struct Command {
+//!     count: u32,
+//!     from: usize,
+//!     to: usize,
+//! }
+//! ```
+//!
+//! Text outside braces must match exactly, and each `{field}` (or `{0}` for
+//! tuple structs) is parsed with its type's `FromStr`. A field runs up to
+//! the text that follows it in the template, or to the end of the line. Use
+//! `{{` and `}}` for literal braces. Each variant of an enum gets a template
+//! of its own, and the first that matches wins. Errors are `aoc::ParseError`s
+//! saying at which column the line stopped matching.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, LitStr};
+
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let format = format_attribute(&input.attrs, input.ident.span())?;
+            parser(quote!(Self), &data.fields, &format)?
+        }
+        Data::Enum(data) => {
+            if data.variants.is_empty() {
+                return Err(syn::Error::new_spanned(input, "AocParse needs at least one variant"));
+            }
+            // Try each variant in turn, keeping the error from the one that
+            // matched the most of the line
+            let attempts = data.variants.iter()
+                .map(|variant| {
+                    let format = format_attribute(&variant.attrs, variant.ident.span())?;
+                    let ident = &variant.ident;
+                    let parser = parser(quote!(Self::#ident), &variant.fields, &format)?;
+                    Ok(quote! {
+                        let attempt = (|| -> ::core::result::Result<Self, ::aoc::ParseError> { #parser })();
+                        match attempt {
+                            ::core::result::Result::Ok(parsed) => return ::core::result::Result::Ok(parsed),
+                            ::core::result::Result::Err(e) => error = ::aoc::ParseError::furthest(error, e),
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                let mut error = ::core::option::Option::None;
+                #(#attempts)*
+                ::core::result::Result::Err(error.unwrap())
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(input, "AocParse does not support unions")),
+    };
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::str::FromStr for #name #type_generics #where_clause {
+            type Err = ::aoc::ParseError;
+
+            fn from_str(text: &str) -> ::core::result::Result<Self, Self::Err> {
+                #body
+            }
+        }
+    })
+}
+
+/// The template in `#[aoc(format = "...")]`.
+fn format_attribute(attrs: &[Attribute], span: Span) -> syn::Result<LitStr> {
+    let mut format = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `format = \"...\"`"))
+            }
+        })?;
+    }
+    format.ok_or_else(|| syn::Error::new(span, "AocParse needs `#[aoc(format = \"...\")]`"))
+}
+
+/// A piece of a template.
+#[derive(Debug, PartialEq)]
+enum Piece {
+    Text(String),
+    Field(String),
+}
+
+/// Splits a template into text and fields, merging escaped braces into the
+/// text around them.
+fn pieces(format: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = vec![];
+    let mut text = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) if c != '{' => field.push(c),
+                        // A field can't hold a `{`, so this one was never closed
+                        _ => return Err(format!("missing `}}` after `{{{field}` in format; write `{{{{` for a literal `{{`")),
+                    }
+                }
+                let field = field.trim();
+                if field.is_empty() {
+                    return Err("empty `{}` in format; name the field it stands for".to_string());
+                }
+                if matches!(pieces.last(), Some(Piece::Field(_))) && text.is_empty() {
+                    return Err(format!("`{{{field}}}` follows another field directly, so where one ends is ambiguous"));
+                }
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(std::mem::take(&mut text)));
+                }
+                pieces.push(Piece::Field(field.to_string()));
+            }
+            '}' => return Err("unmatched `}` in format; write `}}` for a literal one".to_string()),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+    Ok(pieces)
+}
+
+/// The body of a function parsing `text` into `constructor` with `fields`,
+/// following `format`.
+fn parser(constructor: TokenStream2, fields: &Fields, format: &LitStr) -> syn::Result<TokenStream2> {
+    let pieces = pieces(&format.value()).map_err(|e| syn::Error::new(format.span(), e))?;
+
+    // The fields by the names templates use for them: their own, or their
+    // index in a tuple struct
+    let names = fields.iter()
+        .enumerate()
+        .map(|(i, field)| field.ident.as_ref().map_or_else(|| i.to_string(), ToString::to_string))
+        .collect::<Vec<_>>();
+    for name in &names {
+        let uses = pieces.iter().filter(|piece| **piece == Piece::Field(name.clone())).count();
+        if uses != 1 {
+            let problem = if uses == 0 { "is missing from" } else { "appears more than once in" };
+            return Err(syn::Error::new(format.span(), format!("field `{name}` {problem} the format")));
+        }
+    }
+
+    let mut steps = vec![];
+    for (i, piece) in pieces.iter().enumerate() {
+        match piece {
+            Piece::Text(text) => steps.push(quote!(cursor.text(#text)?;)),
+            Piece::Field(name) => {
+                let Some(index) = names.iter().position(|n| n == name) else {
+                    return Err(syn::Error::new(format.span(), format!("there is no field `{name}`")));
+                };
+                let ty = &fields.iter().nth(index).unwrap().ty;
+                let variable = format_ident!("field_{}", index);
+                let until = match pieces.get(i + 1) {
+                    Some(Piece::Text(text)) => quote!(::core::option::Option::Some(#text)),
+                    _ => quote!(::core::option::Option::None),
+                };
+                steps.push(quote!(let #variable = cursor.field::<#ty>(#name, #until)?;));
+            }
+        }
+    }
+
+    let variables = (0..names.len()).map(|index| format_ident!("field_{}", index));
+    let construct = match fields {
+        Fields::Named(_) => {
+            let idents = fields.iter().map(|field| &field.ident);
+            quote!(#constructor { #(#idents: #variables),* })
+        }
+        Fields::Unnamed(_) => quote!(#constructor(#(#variables),*)),
+        Fields::Unit => constructor,
+    };
+
+    Ok(quote! {
+        let mut cursor = ::aoc::parse::Cursor::new(text);
+        #(#steps)*
+        cursor.finish()?;
+        ::core::result::Result::Ok(#construct)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_templates() {
+        let text = |text: &str| Piece::Text(text.to_string());
+        let field = |name: &str| Piece::Field(name.to_string());
+        assert_eq!(
+            pieces("move {count} from {from} to {to}").unwrap(),
+            [text("move "), field("count"), text(" from "), field("from"), text(" to "), field("to")],
+        );
+        assert_eq!(pieces("{0}-{1}").unwrap(), [field("0"), text("-"), field("1")]);
+        assert_eq!(pieces("{{{ 0 }}}").unwrap(), [text("{"), field("0"), text("}")]);
+
+        assert!(pieces("{0}{1}").unwrap_err().contains("ambiguous"));
+        assert!(pieces("{}").is_err());
+        assert!(pieces("a } b").is_err());
+        assert!(pieces("move {count from {from}").unwrap_err().contains("missing `}` after `{count from `"));
+        assert!(pieces("move {count").unwrap_err().contains("missing `}` after `{count`"));
+    }
+}
@@ -0,0 +1,167 @@
+use std::io::BufRead;
+
+use aoc::{schema::{Line, Schema, Violation}, LinesIter};
+
+fn read_as_lines<T: ToString>(path: T) -> LinesIter {
+    aoc::read_as_lines(path).expect("Could not open file")
+}
+
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Expedition(Vec<Elf>);
+
+impl Expedition {
+    fn new() -> Self {
+        Default::default()
+    }
+
+    fn add_elf(&mut self, elf: Elf) {
+        self.0.push(elf);
+        // Can be done in 1 step with `self.0.push(Elf {calories} );`
+    }
+
+    fn max(&self) -> u32 {
+        self.0.iter()
+            .map(|elf| elf.total_calories())    // Replaces each Elf with result of elf.total_calories() in place.
+            .max().unwrap()
+    }
+}
+
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Elf {
+    calories: Vec<u32>,
+}
+
+impl Elf {
+    fn new() -> Self {
+        Self { calories: vec![] }
+    }
+
+    fn add_calorie(&mut self, calorie: u32) {
+        self.calories.push(calorie);
+    }
+
+    fn total_calories(&self) -> u32 {
+        self.calories.iter().sum::<u32>()
+    }
+}
+
+fn expedition_builder<R: BufRead>(mut lines: LinesIter<R>) -> Expedition {
+    let mut expedition = Expedition::new();
+    expedition.add_elf(Elf::new());
+
+    while let Some(line) = lines.next() {
+        let line = line.unwrap();
+
+        if &line[..] == "\n" || line.is_empty() {
+            expedition.add_elf(Elf::new());
+        } else {
+            expedition.0.last_mut().unwrap()                // Get most recent elf
+                .add_calorie(line.parse::<u32>().unwrap())          // Parse line as u32 and add to elf
+        }
+    }
+    expedition
+}
+
+/// Calories carried by the Elf carrying the most Calories.
+pub fn part_one(path: &str) -> aoc::Answer {
+    let lines = read_as_lines(path);
+    let expedition = expedition_builder(lines);
+    expedition.max().into()
+}
+
+/// Calories carried by the top three Elves combined.
+pub fn part_two(path: &str) -> aoc::Answer {
+    let lines = read_as_lines(path);
+    let expedition = expedition_builder(lines);
+
+    let mut expedition_calories = expedition.0.iter()       // Create iterator over elves in expedition
+        .map(|elf| elf.total_calories())        // Replace each elf in the iterator with the sum of its calories
+        .collect::<Vec<u32>>();                                     // Convert iterator back into a vector in order to use Vec::sort()
+
+    expedition_calories.sort();
+    expedition_calories.reverse();                  // Not the most efficient way to do this.
+
+    expedition_calories[..3].iter().sum::<u32>()       // Convert back into iterator to use Iter::sum::<T>()
+        .into()
+}
+
+/// The input's shape: each elf's calories, one number per line, with a blank
+/// line between elves. Part two needs at least three elves.
+pub fn schema() -> Schema {
+    Schema::blocks(Schema::lines(Line::parse::<u32>()))
+        .with(|lines| {
+            let elves = lines.iter().filter(|(_, line)| line.is_empty()).count() + 1;
+            if elves < 3 {
+                vec![Violation::new(None, format!("found {elves} elves, where part two needs at least 3"))]
+            } else {
+                vec![]
+            }
+        })
+}
+
+/// The elves as parsed from the input, and the total each one carries.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let expedition = expedition_builder(read_as_lines(path));
+    let totals = expedition.0.iter()
+        .map(|elf| elf.total_calories())
+        .collect::<Vec<u32>>();
+
+    serde_json::json!({ "input": expedition, "state": { "totals": totals } })
+}
+
+#[cfg(test)]
+mod day_1 {
+    use super::*;
+
+    #[test]
+    fn test_new_elf() {
+        let elf = Elf::new();
+        assert_eq!(elf.calories.len(), 0);
+    }
+
+    #[test]
+    fn test_add_calorie() {
+        let mut elf = Elf::new();
+        elf.add_calorie(1);
+        elf.add_calorie(2);
+        elf.add_calorie(3);
+        assert_eq!(elf.calories, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_total_calorie() {
+        let mut elf = Elf::new();
+        elf.add_calorie(1);
+        elf.add_calorie(2);
+        elf.add_calorie(3);
+        assert_eq!(elf.total_calories(), 6);
+    }
+
+    #[test]
+    fn test_add_elf_to_expedition() {
+        let mut expedition = Expedition::new();
+        expedition.add_elf(Elf {calories: vec![1,2,3] });
+        expedition.add_elf(Elf {calories: vec![10,20,30] });
+        expedition.add_elf(Elf {calories: vec![100,200,300] });
+        assert_eq!(expedition.max(), 600);
+    }
+
+    #[test]
+    fn test_expedition_builder() {
+        let expedition = expedition_builder(aoc::str_as_lines("1\n2\n\n10\n\n4\n"));
+        let calories = expedition.0.iter().map(|elf| elf.calories.clone()).collect::<Vec<_>>();
+        assert_eq!(calories, [vec![1, 2], vec![10], vec![4]]);
+        assert_eq!(expedition.max(), 10);
+    }
+
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(24000_u32));
+        assert_eq!(super::part_two(CRLF), aoc::Answer::from(45000_u32));
+    }
+}
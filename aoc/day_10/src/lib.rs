@@ -1,8 +1,9 @@
-use gif::{Encoder, Frame};
-use std::borrow::Cow;
-
-static mut FRAME_BUFFER: [u8; 40 * 7] = [0; 40 * 7];
+use aoc::{schema::{self, Line, Schema}, simulation::{Frame, Simulation}, Answer, ParseError};
+use gif::Encoder;
+use std::{borrow::Cow, collections::VecDeque};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+// ANCHOR: clock
 pub struct Clock{
     /// Value of register X
     x: i32,
@@ -13,6 +14,7 @@ pub struct Clock{
     /// CRT output (part 2)
     pub buffer: String,
 }
+// ANCHOR_END: clock
 
 impl Clock {
     pub fn new() -> Self {
@@ -23,10 +25,19 @@ impl Clock {
         self.inc_cycle();
     }
 
-    pub fn addx(&mut self, n: i32) {
-        self.inc_cycle();
-        self.inc_cycle();
-        self.x += n;
+    /// Sum of the signal strengths sampled during the 20th, 60th, ... 220th
+    /// cycles.
+    pub fn signal_strength(&self) -> i32 {
+        self.total
+    }
+
+    /// Splits the CRT output into its 40 pixel wide rows.
+    pub fn screen(&self) -> Vec<String> {
+        self.buffer.chars()
+            .collect::<Vec<char>>()
+            .chunks(40)
+            .map(|row| row.iter().collect())
+            .collect()
     }
 
     fn inc_cycle(&mut self) {
@@ -38,6 +49,7 @@ impl Clock {
         }
         
         self.cycle += 1;
+        tracing::trace!(cycle = self.cycle, x = self.x, "cycle");
 
         // Calculate part 1
         if (self.cycle - 20) % 40 == 0 && self.cycle < 221 {
@@ -45,51 +57,104 @@ impl Clock {
         }
     }
 
-    fn update_buffer(&self) {
+    // ANCHOR: frame
+    /// The CRT as it is drawn: lit pixels green, the pixel being drawn
+    /// white, and register X as a red dot on an extra row at the bottom.
+    pub fn frame(&self) -> Frame {
         // Clear buffer (set to grey) 
-        unsafe { FRAME_BUFFER = [0; 40 * 7] }
-                
+        let mut frame = Frame::new(40, 7);
 
         // Write visible cells as green
         for (i, c) in self.buffer.char_indices() {
             if c == '#' {
-                unsafe { FRAME_BUFFER[i] = 2 };
+                frame.pixels[i] = 2;
             }
         }
 
         // Write clock number pointer as white
-        unsafe { FRAME_BUFFER[self.cycle as usize] = 3 }
+        if let Some(pixel) = frame.pixels.get_mut(self.cycle as usize) {
+            *pixel = 3;
+        }
 
         // Write register X as three pixel wide, red dot on bottom row
         for i in self.x - 1 .. self.x + 1 {
             if i >= 0 && i < 40 {
-                let dot = (i + (40 * 6)) as usize;
-                unsafe { FRAME_BUFFER[dot] = 1 };
+                frame.set(i as usize, 6, 1);
             }
         }
+
+        frame
     }
+    // ANCHOR_END: frame
+}
 
-    pub fn write_to_gif(&self, encoder: &mut Encoder<std::fs::File>) {
-        self.update_buffer();
-        let frame = Frame {
-            width: 40,
-            height: 7,
-            buffer: unsafe { Cow::Borrowed(&FRAME_BUFFER) },
-            ..Default::default()
-        };
+// ANCHOR: write_frame
+/// Adds `frame` to a GIF whose palette has the four colors `Clock::frame`
+/// draws with.
+pub fn write_frame(encoder: &mut Encoder<std::fs::File>, frame: &Frame) {
+    let frame = gif::Frame {
+        width: frame.width as u16,
+        height: frame.height as u16,
+        buffer: Cow::Borrowed(&frame.pixels),
+        ..Default::default()
+    };
+
+    encoder.write_frame(&frame).unwrap();
+}
+// ANCHOR_END: write_frame
+
+// ANCHOR: cpu
+/// A `Clock` running a program, one cycle per tick.
+pub struct Cpu {
+    pub clock: Clock,
+    /// Instructions yet to start
+    program: VecDeque<Token>,
+    /// What an `addx` one cycle in will add to X when its second ends
+    pending: Option<i32>,
+}
+
+impl Cpu {
+    pub fn new(program: impl IntoIterator<Item = Token>) -> Self {
+        Self { clock: Clock::new(), program: program.into_iter().collect(), pending: None }
+    }
+}
+
+impl Simulation for Cpu {
+    fn step(&mut self) {
+        if let Some(n) = self.pending.take() {
+            self.clock.inc_cycle();
+            self.clock.x += n;
+            return;
+        }
+        match self.program.pop_front() {
+            Some(Token::Addx(n)) => {
+                self.clock.inc_cycle();
+                self.pending = Some(n);
+            }
+            Some(Token::Noop) => self.clock.noop(),
+            None => {}
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.pending.is_none() && self.program.is_empty()
+    }
 
-        encoder.write_frame(&frame).unwrap();
+    fn render(&self) -> Option<Frame> {
+        Some(self.clock.frame())
     }
 }
+// ANCHOR_END: cpu
 
-//// ANCHOR: token
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+// ANCHOR: token
 pub enum Token {
     Addx(i32),
     Noop,
 }
-//// ANCHOR_END: token
+// ANCHOR_END: token
 
-//// ANCHOR: tryfrom
+// ANCHOR: tryfrom
 impl TryFrom::<String> for Token {
     type Error = TokenParserError;
 
@@ -120,7 +185,7 @@ impl TryFrom::<String> for Token {
         }
     }
 }
-//// ANCHOR_END: tryfrom
+// ANCHOR_END: tryfrom
 
 #[derive(Debug)]
 /// Error that may be thrown while parsing commands
@@ -146,4 +211,89 @@ impl std::fmt::Display for TokenParserError {
     }
 }
 
-impl std::error::Error for TokenParserError {}
\ No newline at end of file
+impl std::error::Error for TokenParserError {}
+
+/// Runs every instruction in the input program on a fresh `Clock`.
+pub fn run_program(path: &str) -> Clock {
+    run_program_from_lines(aoc::read_as_lines(path).unwrap())
+}
+
+/// Like `run_program`, but reads the program from `lines`.
+pub fn run_program_from_lines<R: std::io::BufRead>(lines: aoc::LinesIter<R>) -> Clock {
+    let program = lines.map(|line| Token::try_from(line.unwrap()).unwrap());
+    let mut cpu = Cpu::new(program);
+    cpu.run();
+    cpu.clock
+}
+
+/// Sum of the six sampled signal strengths.
+pub fn part_one(path: &str) -> Answer {
+    run_program(path).signal_strength().into()
+}
+
+/// The image drawn on the CRT. Read it with `aoc::answer::ocr`.
+pub fn part_two(path: &str) -> Answer {
+    Answer::Grid(run_program(path).screen())
+}
+
+/// The input's shape: the program, one `noop` or `addx <n>` per line.
+pub fn schema() -> Schema {
+    Schema::lines(Line::new(|line: &str| {
+        schema::unpadded(line)?;
+        Token::try_from(line.to_string()).map_err(|e| ParseError::new(1, e.to_string()))?;
+        Ok::<_, ParseError>(())
+    }))
+}
+
+/// The program as parsed from the input, and the clock after running it,
+/// along with both parts' results.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let program = aoc::read_as_lines(path).unwrap()
+        .map(|line| Token::try_from(line.unwrap()).unwrap())
+        .collect::<Vec<Token>>();
+    let clock = run_program(path);
+
+    serde_json::json!({
+        "input": program,
+        "state": {
+            "clock": clock,
+            "signal_strength": clock.signal_strength(),
+            "screen": clock.screen(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(13140_i32));
+        assert_eq!(super::part_two(CRLF).to_string().lines().next(), Some("##..##..##..##..##..##..##..##..##..##.."));
+    }
+
+    #[test]
+    fn inline_program() {
+        let clock = super::run_program_from_lines(aoc::str_as_lines("noop\naddx 3\naddx -5\n"));
+        assert_eq!((clock.cycle, clock.x), (5, -1));
+    }
+
+    #[test]
+    fn one_cycle_per_tick() {
+        use aoc::simulation::{Driver, Simulation};
+
+        let program = [super::Token::Noop, super::Token::Addx(3), super::Token::Addx(-5)];
+        let mut xs = vec![];
+        let outcome = Driver::new()
+            .observe(|_, cpu: &super::Cpu| xs.push(cpu.clock.x))
+            .run(&mut super::Cpu::new(program));
+        assert_eq!(outcome.ticks, 5);
+        // X changes only as each `addx` finishes its second cycle
+        assert_eq!(xs, [1, 1, 4, 4, -1]);
+
+        let frame = super::Cpu::new([]).render().unwrap();
+        assert_eq!(frame.to_text(&['.', 'x', '#', '@'])[6], "xx......................................");
+    }
+}
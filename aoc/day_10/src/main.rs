@@ -1,8 +1,9 @@
 use day_10::*;
-use aoc;
-use std::{fs, borrow::Cow};
-use gif::{Encoder, Repeat};
+use aoc::simulation::Driver;
+use std::fs;
+use gif::Encoder;
 
+// ANCHOR: build_encoder
 fn build_encoder(path: &str) -> Encoder<fs::File> {
     // Encode colors as trios of (r, g, b) hex values
     let color_map = &[
@@ -11,28 +12,27 @@ fn build_encoder(path: &str) -> Encoder<fs::File> {
         0, 0xFF, 0,         // Color 2 = green(0,255,4)
         0xFF, 0xFF, 0xFF    // Color 3 = white(255,255,255)
     ];
-    let (width, height) = (40u16, 6u16);
+
+    // I add a 7th row to show the register pointer
+    let (width, height) = (40u16, 7u16);
     let image = fs::File::create(path).unwrap();
     let mut encoder = Encoder::new(image, width, height, color_map).unwrap();
     encoder.set_repeat(gif::Repeat::Infinite).unwrap();
 
     encoder
 }
+// ANCHOR_END: build_encoder
 
+// ANCHOR: main
 fn main() {
     let lines = aoc::read_as_lines("../inputs/day_10.txt").unwrap();
+    let program = lines.map(|line| Token::try_from(line.unwrap()).unwrap());
 
     let mut encoder = build_encoder("crt.gif");
 
-    let mut clock = Clock::new();
-    
-    for line in lines {
-        let line = line.unwrap();
-        let token = Token::try_from(line).unwrap();
-        match token {
-            Token::Addx(n) => clock.addx(n),
-            Token::Noop => clock.noop(),
-        }
-        clock.write_to_gif(&mut encoder);
-    }
-}
\ No newline at end of file
+    // A frame for every cycle of the clock
+    Driver::new()
+        .on_frame(|_, frame| write_frame(&mut encoder, frame))
+        .run(&mut Cpu::new(program));
+}
+// ANCHOR_END: main
@@ -1,12 +1,17 @@
+use std::cell::RefCell;
 use std::collections::VecDeque;
-use aoc;
+use aoc::{self, schema::{Line, Schema, Violation}, simulation::{Driver, Simulation}, AocParse, LinesIter, ParseError};
 use std::fmt::Debug;
+use std::io::BufRead;
+use std::str::FromStr;
 
 pub struct Monkey {
     pub id: usize,
     pub items: VecDeque<usize>,
     pub operation: Box<dyn Fn(usize) -> usize>,  // NEW
     pub test: Box<dyn Fn(usize) -> bool>,        // NEW
+    /// What `test` checks worry levels are divisible by
+    pub divisor: usize,
     pub target_if_true: usize,
     pub target_if_false: usize,
     pub touch_counter: usize,
@@ -14,7 +19,7 @@ pub struct Monkey {
 
 impl Monkey {
     /// Initialize a new monkey from a Lines iterator.
-    pub fn new(lines: &mut aoc::LinesIter) -> Option<Self> {
+    pub fn new<R: BufRead>(lines: &mut LinesIter<R>) -> Option<Self> {
         // Check if first line is "None". If so, return early. Otherwise, continue parsing.
         let first_line: String = if let Some(line) = lines.next() {
             line.unwrap()
@@ -27,7 +32,8 @@ impl Monkey {
         let id = Self::parse_id(first_line);
         let items = Self::parse_items(lines.next().unwrap().unwrap());
         let operation = Self::parse_operation(lines.next().unwrap().unwrap());
-        let test = Self::parse_test(lines.next().unwrap().unwrap());
+        let divisor = Self::parse_divisor(lines.next().unwrap().unwrap());
+        let test = Box::new(move |x: usize| x % divisor == 0);
         let target_if_true = Self::parse_true_monkey(lines.next().unwrap().unwrap());
         let target_if_false = Self::parse_false_monkey(lines.next().unwrap().unwrap());
         let _ = lines.next();
@@ -37,6 +43,7 @@ impl Monkey {
             items,
             operation,
             test,
+            divisor,
             target_if_true,
             target_if_false,
             touch_counter: 0,
@@ -47,16 +54,23 @@ impl Monkey {
     /// Vec<(usize, usize)> where item.0 is the target monkey and item.1
     /// is the item to add to the stack.
     pub fn throw_items(&mut self) -> Vec<(usize, usize)> {
+        self.throw_items_with(|worry| worry / 3)
+    }
+
+    /// A single turn, where `relieve` is applied to each item's worry level
+    /// after it is inspected, rather than dividing it by three.
+    pub fn throw_items_with(&mut self, relieve: impl Fn(usize) -> usize) -> Vec<(usize, usize)> {
         let mut thrown_items: Vec<(usize, usize)> = vec![];
 
         while let Some(item) = self.items.pop_front() {
             self.touch_counter += 1;
-            let item = (self.operation)(item) / 3;
-            match (self.test)(item) {
-                true => thrown_items.push((self.target_if_true, item)),
-                false => thrown_items.push((self.target_if_false, item)),
-
-            }
+            let item = relieve((self.operation)(item));
+            let target = match (self.test)(item) {
+                true => self.target_if_true,
+                false => self.target_if_false,
+            };
+            tracing::trace!(monkey = self.id, item, target, "throw");
+            thrown_items.push((target, item));
         }
         
         thrown_items
@@ -65,9 +79,7 @@ impl Monkey {
     /// Pulls monkey.id from the first line of each monkey block the input
     fn parse_id(line: String) -> usize { 
         // Example input: "Monkey 0:"
-        assert_eq!(&line[..7], "Monkey ");
-        let line = line.strip_suffix(':').unwrap();
-        (&line[7..]).parse().unwrap()
+        parse::<Id>(&line).0
     }
 
     /// Creates a VecDeque populated with items in the second line of the block
@@ -85,56 +97,138 @@ impl Monkey {
     /// Creates a closure matching `operation`, the third line of the block
     fn parse_operation(line: String) -> Box<dyn Fn(usize) -> usize> { 
         // Example input: "  Operation: new = old + 2"
-        let line = line.trim();
-        assert_eq!(&line[..21], "Operation: new = old ");
-
-        let tokens = (&line[21..]).split_whitespace().collect::<Vec<&str>>();
-        match (tokens[0], tokens[1]) {
-            ("+", "old") => {
-                let closure = move |x: usize| x + x;
-                Box::new(closure)
-            },
-            ("*", "old") => {
-                let closure = move |x: usize| x * x;
-                Box::new(closure)
-            },
-            ("+", n) => {
-                let operand = n.parse::<usize>().unwrap();
-                let closure = move |x: usize| x + operand;
-                Box::new(closure)
-            },
-            ("*", n) => {
-                let operand = n.parse::<usize>().unwrap();
-                let closure = move |x: usize| x * operand;
-                Box::new(closure)
-            },
-            _ => panic!("Unexpect token in line {}", line)
+        match parse::<Operation>(&line) {
+            Operation::Double => Box::new(|x: usize| x + x),
+            Operation::Square => Box::new(|x: usize| x * x),
+            Operation::Add(operand) => Box::new(move |x: usize| x + operand),
+            Operation::Multiply(operand) => Box::new(move |x: usize| x * operand),
         }
     }
 
-    /// Creates a closure matching `test`, the fourth line of the block
-    fn parse_test(line: String) -> Box<dyn Fn(usize) -> bool> { 
+    /// Pulls what worry levels are tested for divisibility by from the fourth
+    /// line
+    fn parse_divisor(line: String) -> usize {
         // Example input: "  Test: divisible by 11"
-        assert_eq!(&line[..21], "  Test: divisible by ");
-        let divisor = (&line[21..]).parse::<usize>().unwrap();
-
-        let closure = { move |x: usize| x % divisor == 0 };
-
-        Box::new(closure)
+        parse::<Test>(&line).0
     }
 
     /// Pulls the id of the target monkey (if test passes) from the fifth line
     fn parse_true_monkey(line: String) -> usize { 
         // Example input: "    If true: throw to monkey 1"
-        assert_eq!(&line[..29], "    If true: throw to monkey ");
-        (&line[29..]).parse().unwrap()
+        parse::<IfTrue>(&line).0
     }
     
     /// Pulls the id of the target monkey (if test fails) from the fifth line
     fn parse_false_monkey(line: String) -> usize {
         // Example input: "    If false: throw to monkey 4"
-        assert_eq!(&line[..30], "    If false: throw to monkey ");
-        (&line[30..]).parse().unwrap()
+        parse::<IfFalse>(&line).0
+    }
+}
+
+/// Lines of a monkey block, without their indentation.
+#[derive(AocParse)]
+#[aoc(format = "Monkey {0}:")]
+struct Id(usize);
+
+#[derive(AocParse)]
+enum Operation {
+    #[aoc(format = "Operation: new = old + old")]
+    Double,
+    #[aoc(format = "Operation: new = old * old")]
+    Square,
+    #[aoc(format = "Operation: new = old + {0}")]
+    Add(usize),
+    #[aoc(format = "Operation: new = old * {0}")]
+    Multiply(usize),
+}
+
+#[derive(AocParse)]
+#[aoc(format = "Test: divisible by {0}")]
+struct Test(usize);
+
+#[derive(AocParse)]
+#[aoc(format = "If true: throw to monkey {0}")]
+struct IfTrue(usize);
+
+#[derive(AocParse)]
+#[aoc(format = "If false: throw to monkey {0}")]
+struct IfFalse(usize);
+
+/// Parses one line of a monkey block, ignoring its indentation.
+fn parse<T: FromStr<Err = aoc::ParseError>>(line: &str) -> T {
+    line.trim().parse().unwrap_or_else(|e| panic!("Invalid line {line:?}: {e}"))
+}
+
+/// The input's shape: a block of six lines for each monkey, numbered from 0
+/// in order, with a blank line between blocks. Tests must divide by at least
+/// 1, and monkeys throw only to other monkeys that exist.
+pub fn schema() -> Schema {
+    Schema::blocks(Schema::exactly([
+        indented(0, |_: Id| Ok(())),
+        Line::new(|line: &str| {
+            let items = line.strip_prefix("  Starting items: ")
+                .ok_or_else(|| ParseError::new(1, "expected \"  Starting items: \""))?;
+            let mut column = 19;
+            for item in items.split(", ") {
+                item.parse::<usize>()
+                    .map_err(|e| ParseError::new(column, format!("invalid item {item:?}: {e}")))?;
+                column += item.chars().count() + 2;
+            }
+            Ok::<_, ParseError>(())
+        }),
+        indented(2, |_: Operation| Ok(())),
+        indented(2, |Test(divisor)| if divisor == 0 { Err("divisible by 0".to_string()) } else { Ok(()) }),
+        indented(4, |_: IfTrue| Ok(())),
+        indented(4, |_: IfFalse| Ok(())),
+    ]))
+    .with(|lines| {
+        let blocks = lines.split(|(_, line)| line.is_empty()).collect::<Vec<_>>();
+        let mut violations = vec![];
+        for (i, block) in blocks.iter().enumerate() {
+            let (number, id) = block[0];
+            if parse::<Id>(id).0 != i {
+                violations.push(Violation::at(number, format!("expected monkey {i}, as monkeys are numbered in order")));
+            }
+            let targets = [(block[4].0, parse::<IfTrue>(block[4].1).0), (block[5].0, parse::<IfFalse>(block[5].1).0)];
+            for (number, target) in targets {
+                if target >= blocks.len() {
+                    violations.push(Violation::at(number, format!("there is no monkey {target}")));
+                } else if target == i {
+                    violations.push(Violation::at(number, format!("monkey {i} throws to itself")));
+                }
+            }
+        }
+        violations
+    })
+}
+
+/// A line of a monkey block: `indent` spaces, then a `T` that `check` accepts.
+fn indented<T>(indent: usize, check: impl Fn(T) -> Result<(), String> + 'static) -> Line
+where
+    T: FromStr<Err = ParseError>,
+{
+    Line::new(move |line: &str| {
+        let text = line.get(indent..)
+            .filter(|text| line[..indent].bytes().all(|b| b == b' ') && !text.starts_with(' '))
+            .ok_or_else(|| ParseError::new(1, format!("expected {indent} spaces of indentation")))?;
+        let value = text.parse::<T>().map_err(|e| ParseError::new(e.column + indent, e.message))?;
+        check(value).map_err(|message| ParseError::new(indent + 1, message))
+    })
+}
+
+/// The operation and test are closures, so they are left out.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Monkey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Monkey", 5)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("items", &self.items)?;
+        state.serialize_field("target_if_true", &self.target_if_true)?;
+        state.serialize_field("target_if_false", &self.target_if_false)?;
+        state.serialize_field("touch_counter", &self.touch_counter)?;
+        state.end()
     }
 }
 
@@ -144,6 +238,110 @@ impl Debug for Monkey {
     }
 }
 
+/// Reads every monkey block in the input.
+///
+/// In our loop of rounds, we will need to pull from one monkey and push to 
+/// another, all without breaking the loop. The borrow checker won't let us
+/// do that with monkeys stored in Vec<Monkey>, so we wrap them in RefCell
+/// to allow for run-time borrow checks.
+pub fn parse_monkeys(path: &str) -> Vec<RefCell<Monkey>> {
+    parse_monkeys_from_lines(aoc::read_as_lines(path).unwrap())
+}
+
+/// Like `parse_monkeys`, but reads the input from `lines`.
+pub fn parse_monkeys_from_lines<R: BufRead>(mut lines: LinesIter<R>) -> Vec<RefCell<Monkey>> {
+    let mut monkeys: Vec<RefCell<Monkey>> = vec![];
+    while let Some(monkey) = Monkey::new(&mut lines) {
+        monkeys.push(RefCell::new(monkey));
+    }
+    monkeys
+}
+
+/// Simulates `rounds` rounds of every monkey taking its turn in order.
+pub fn play_rounds(monkeys: &[RefCell<Monkey>], rounds: usize) {
+    play_rounds_with(monkeys, rounds, |worry| worry / 3);
+}
+
+/// Like `play_rounds`, but with `relieve` applied to worry levels after each
+/// inspection. See `Monkey::throw_items_with`.
+pub fn play_rounds_with(monkeys: &[RefCell<Monkey>], rounds: usize, relieve: impl Fn(usize) -> usize) {
+    Driver::new().limit(rounds).run(&mut Rounds::new(monkeys, relieve));
+}
+
+/// Monkeys playing keep away, one round per tick. They never stop, so run
+/// them with a `Driver::limit`.
+pub struct Rounds<'a, F> {
+    pub monkeys: &'a [RefCell<Monkey>],
+    /// Applied to worry levels after each inspection
+    relieve: F,
+    /// Rounds played so far
+    pub round: usize,
+}
+
+impl<'a, F: Fn(usize) -> usize> Rounds<'a, F> {
+    pub fn new(monkeys: &'a [RefCell<Monkey>], relieve: F) -> Self {
+        Self { monkeys, relieve, round: 0 }
+    }
+}
+
+impl<F: Fn(usize) -> usize> Simulation for Rounds<'_, F> {
+    fn step(&mut self) {
+        for monkey in self.monkeys.iter() {
+            let items = monkey.borrow_mut().throw_items_with(&self.relieve);
+            for (monkey_id, item) in items {
+                self.monkeys[monkey_id].borrow_mut().items.push_back(item);
+            }
+        }
+        self.round += 1;
+        tracing::debug!(round = self.round, monkeys = ?self.monkeys, "round complete");
+    }
+}
+
+/// Number of items each monkey inspected, largest to smallest.
+pub fn touches(monkeys: &[RefCell<Monkey>]) -> Vec<usize> {
+    let mut touches = monkeys.iter()
+        .map(|monkey| monkey.borrow().touch_counter)
+        .collect::<Vec<_>>();
+
+    // Sorts in reverse order, largest to smallest.
+    touches.sort_by(|a, b|b.cmp(a));
+    touches
+}
+
+/// Level of monkey business after 20 rounds.
+pub fn part_one(path: &str) -> aoc::Answer {
+    let monkeys = parse_monkeys(path);
+    play_rounds(&monkeys, 20);
+    let touches = touches(&monkeys);
+    (touches[0] * touches[1]).into()
+}
+
+/// Level of monkey business after 10000 rounds, with worry levels no longer
+/// divided by three. They would grow without bound, so they are kept modulo
+/// the least common multiple of the monkeys' divisors instead, which changes
+/// none of the tests' results.
+pub fn part_two(path: &str) -> aoc::Answer {
+    let monkeys = parse_monkeys(path);
+    let modulus = aoc::math::lcm_all(monkeys.iter().map(|monkey| monkey.borrow().divisor))
+        .expect("divisors' least common multiple overflows");
+    play_rounds_with(&monkeys, 10_000, |worry| worry % modulus);
+    let touches = touches(&monkeys);
+    (touches[0] * touches[1]).into()
+}
+
+/// The monkeys as parsed from the input, and after the 20 rounds of part one.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let monkeys = parse_monkeys(path);
+    let input = serde_json::to_value(&monkeys).unwrap();
+
+    play_rounds(&monkeys, 20);
+    serde_json::json!({
+        "input": input,
+        "state": { "monkeys": monkeys, "touches": touches(&monkeys) },
+    })
+}
+
 #[cfg(test)]
 mod day_11 {
     #[test]
@@ -167,10 +365,13 @@ mod day_11 {
     #[test]
     fn parse_test() {
         let input = String::from("  Test: divisible by 11");
-        let divisible_by_11 = super::Monkey::parse_test(input);
+        assert_eq!(super::Monkey::parse_divisor(input), 11);
 
-        assert_eq!(divisible_by_11(22), true);
-        assert_eq!(divisible_by_11(23), false);
+        let block = "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = old * 19\n  Test: divisible by 23\n    If true: throw to monkey 2\n    If false: throw to monkey 3\n";
+        let monkey = super::Monkey::new(&mut aoc::str_as_lines(block)).unwrap();
+        assert_eq!(monkey.divisor, 23);
+        assert!((monkey.test)(46));
+        assert!(!(monkey.test)(47));
     }
 
     #[test]
@@ -191,4 +392,64 @@ mod day_11 {
         let operation = super::Monkey::parse_operation(input);
         assert_eq!(operation(3), 3 + 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn new_monkey() {
+        let mut lines = aoc::str_as_lines(concat!(
+            "Monkey 3:\n",
+            "  Starting items: 74\n",
+            "  Operation: new = old + 3\n",
+            "  Test: divisible by 17\n",
+            "    If true: throw to monkey 0\n",
+            "    If false: throw to monkey 1\n",
+        ));
+        let mut monkey = super::Monkey::new(&mut lines).unwrap();
+        assert_eq!((monkey.id, monkey.divisor, monkey.target_if_true, monkey.target_if_false), (3, 17, 0, 1));
+        // (74 + 3) / 3 = 25, which is not divisible by 17
+        assert_eq!(monkey.throw_items(), [(1, 25)]);
+        assert!(super::Monkey::new(&mut lines).is_none());
+    }
+
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(10605_u64));
+        assert_eq!(super::part_two(CRLF), aoc::Answer::from(2713310158_u64));
+    }
+
+    #[test]
+    fn rounds_tick_one_at_a_time() {
+        use aoc::simulation::{Driver, Simulation};
+
+        let monkeys = super::parse_monkeys(CRLF);
+        let mut rounds = super::Rounds::new(&monkeys, |worry| worry);
+        let mut inspected = vec![];
+        let outcome = Driver::new()
+            .limit(1)
+            .observe(|round, rounds: &super::Rounds<_>| {
+                inspected.push((round, rounds.monkeys.iter().map(|m| m.borrow().touch_counter).collect::<Vec<_>>()));
+            })
+            .run(&mut rounds);
+        assert_eq!((outcome.ticks, outcome.done, rounds.round), (1, false, 1));
+        // As in the puzzle's part two, where worry is never relieved
+        assert_eq!(inspected, [(1, vec![2, 4, 3, 6])]);
+
+        rounds.step();
+        assert_eq!(rounds.round, 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dump() {
+        let dump = super::dump(CRLF);
+        assert_eq!(
+            dump["input"][0],
+            serde_json::json!({
+                "id": 0, "items": [79, 98], "target_if_true": 2, "target_if_false": 3, "touch_counter": 0,
+            }),
+        );
+        assert_eq!(dump["state"]["monkeys"][3]["touch_counter"], 105);
+        assert_eq!(dump["state"]["touches"], serde_json::json!([105, 101, 95, 7]));
+    }
+}
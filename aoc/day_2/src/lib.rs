@@ -0,0 +1,131 @@
+use aoc::{read_as_lines, schema::{Line, Schema}, Answer};
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+enum Hand {
+    Rock(u32),
+    Paper(u32),
+    Scissors(u32),
+}
+
+impl TryFrom<char> for Hand {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'A' | 'X' => Ok(Self::Rock(1)),
+            'B' | 'Y' => Ok(Self::Paper(2)),
+            'C' | 'Z' => Ok(Self::Scissors(3)),
+            _ => Err("Unexpected character"),
+        }
+    }
+}
+
+fn game(my_hand: &Hand, opp_hand: &Hand) -> u32 {
+    use Hand::*;            // Lets us write `Rock` instead of `Hand::Rock`, etc.
+    match (my_hand, opp_hand) {
+        (Rock(n), Scissors(_)) | (Paper(n), Rock(_))     | (Scissors(n), Paper(_))    => 6 + n,  // win
+        (Rock(n), Rock(_))     | (Paper(n), Paper(_))    | (Scissors(n), Scissors(_)) => 3 + n,  // tie
+        (Rock(n), Paper(_))    | (Paper(n), Scissors(_)) | (Scissors(n), Rock(_))     => 0 + n,  // loss
+    }
+}
+
+/// Total score if every round is played as the strategy guide suggests.
+pub fn part_one(path: &str) -> Answer {
+    let lines = read_as_lines(path).unwrap();
+
+    let mut total = 0;
+    
+    for line in lines {
+        let line = line.unwrap();
+        let mut chars = line.chars();
+        let opp_hand = Hand::try_from(chars.next().unwrap()).unwrap();
+        let my_hand = Hand::try_from(chars.last().unwrap()).unwrap();
+        total += game(&my_hand, &opp_hand);
+    }
+
+    total.into()
+}
+
+/// The input's shape: one round per line, the opponent's hand (`A`, `B` or
+/// `C`) then a space and the suggested response (`X`, `Y` or `Z`).
+pub fn schema() -> Schema {
+    Schema::lines(Line::new(|line: &str| match line.as_bytes() {
+        [b'A'..=b'C', b' ', b'X'..=b'Z'] => Ok(()),
+        _ => Err(format!("expected a round like \"A Y\", found {line:?}")),
+    }))
+}
+
+/// Every round as parsed from the input (opponent's hand first), and the
+/// score of each.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let rounds = read_as_lines(path).unwrap()
+        .map(|line| {
+            let line = line.unwrap();
+            let mut chars = line.chars();
+            let opp_hand = Hand::try_from(chars.next().unwrap()).unwrap();
+            let my_hand = Hand::try_from(chars.last().unwrap()).unwrap();
+            (opp_hand, my_hand)
+        })
+        .collect::<Vec<(Hand, Hand)>>();
+    let scores = rounds.iter()
+        .map(|(opp_hand, my_hand)| game(my_hand, opp_hand))
+        .collect::<Vec<u32>>();
+
+    serde_json::json!({ "input": rounds, "state": { "scores": scores } })
+}
+
+#[cfg(test)]
+mod day_2 {
+    use std::f32::consts::E;
+
+    use super::*;
+
+    #[test]
+    fn test_game() {
+        let win = (Hand::Rock(1), Hand::Scissors(0));       // (My hand, opponent hand)
+        let tie = (Hand::Paper(2), Hand::Paper(0));
+        let loss = (Hand::Scissors(3), Hand::Rock(0));
+        assert_eq!(game(&win.0, &win.1), 6 + 1);     // win score + hand score
+        assert_eq!(game(&tie.0, &tie.1), 3 + 2);
+        assert_eq!(game(&loss.0,&loss.1), 0 + 3);
+    }
+
+    #[test]
+    fn test_from_char() {
+        assert_eq!(Hand::try_from('A'), Ok(Hand::Rock(1)));
+        assert_eq!(Hand::try_from('B'), Ok(Hand::Paper(2)));
+        assert_eq!(Hand::try_from('C'), Ok(Hand::Scissors(3)));
+        assert!(Hand::try_from('h').is_err());
+        // etc...
+    }
+
+    #[test]
+    fn test_hs() {
+        use std::collections::HashSet;
+
+        let hs1 = HashSet::from([1,2,3]);
+        let hs2 = HashSet::from([2,3,4]);
+
+            // Iterate over first hashset
+        let mut intersection = hs1.iter()       
+            // Check if each element is in second hashset
+            .filter(move |element| hs2.contains(element));   
+
+        let element = intersection.next().unwrap();
+        assert!(*element == 2 || *element == 3);
+
+        let element = intersection.next().unwrap();
+        assert!(*element == 2 || *element == 3);
+
+        assert!(intersection.next().is_none());
+    }
+
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(15_u32));
+    }
+}
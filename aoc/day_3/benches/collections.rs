@@ -0,0 +1,31 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// `intersection` as it was, with a `HashSet` for each compartment.
+mod before {
+    use std::collections::HashSet;
+
+    pub fn intersection(line: String) -> char {
+        let middle = line.len() / 2;
+        let left_side: HashSet<char> = line[..middle].chars().collect();
+        let right_side: HashSet<char> = line[middle..].chars().collect();
+        *left_side.intersection(&right_side).next().unwrap()
+    }
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let rucksacks = aoc::read_as_lines("../inputs/day_3.txt").unwrap()
+        .map(|line| line.unwrap())
+        .collect::<Vec<String>>();
+
+    let mut group = c.benchmark_group("intersection");
+    group.bench_function("hash-set",
+        |b| b.iter(|| rucksacks.iter().map(|r| before::intersection(r.clone())).collect::<Vec<_>>())
+    );
+    group.bench_function("bit-set",
+        |b| b.iter(|| rucksacks.iter().map(|r| day_3::intersection(r.clone())).collect::<Vec<_>>())
+    );
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
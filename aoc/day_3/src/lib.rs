@@ -0,0 +1,90 @@
+use aoc::*;
+use aoc::collections::{FastHashSet, FixedBitSet};
+use aoc::schema::{Line, Schema};
+
+fn priority(c: &char) -> u32 {
+    let val = *c as u32;
+    match val {
+        65..=90 => val-65+27,
+        97..=122 => val-96,
+        _ => 0,
+    }
+}
+
+/// The one item type found in both compartments of a rucksack.
+pub fn intersection(line: String) -> char {
+    let shared = if line.is_ascii() {
+        // Every ASCII character fits in the set's 128 bits
+        let (left, right) = line.split_at(line.len() / 2);
+        let items = |side: &str| side.bytes().map(usize::from).collect::<FixedBitSet<2>>();
+        items(left).intersection(&items(right)).iter().map(|c| c as u8 as char).collect::<Vec<_>>()
+    } else {
+        let items = line.chars().collect::<Vec<_>>();
+        let (left, right) = items.split_at(items.len() / 2);
+        let left = left.iter().collect::<FastHashSet<_>>();
+        let right = right.iter().collect::<FastHashSet<_>>();
+        left.intersection(&right).map(|&&c| c).collect()
+    };
+    let mut intersection_iter = shared.into_iter();
+
+    let intersection = intersection_iter.next().unwrap();
+    assert!(intersection_iter.next().is_none());    // Assert there is only one element in intersection.
+
+    intersection
+}
+
+/// Sum of the priorities of the item types found in both compartments.
+pub fn part_one(path: &str) -> Answer {
+    let lines = read_as_lines(path).unwrap();
+
+    lines.map(|line| {
+        let c = intersection(line.unwrap());
+        priority(&c)
+    }).sum::<u32>().into()
+}
+
+/// The input's shape: one rucksack per line, an even number of letters so it
+/// splits into two compartments.
+pub fn schema() -> Schema {
+    Schema::lines(Line::new(|line: &str| {
+        if let Some((i, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::new(i + 1, format!("expected letters, found {c:?}")));
+        }
+        if line.len() % 2 == 1 {
+            return Err(ParseError::new(1, format!("{} items do not split into two compartments", line.len())));
+        }
+        Ok(())
+    }))
+}
+
+/// Every rucksack in the input, and the item type found in both of its
+/// compartments along with that item's priority.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let rucksacks = read_as_lines(path).unwrap()
+        .map(|line| line.unwrap())
+        .collect::<Vec<String>>();
+    let shared = rucksacks.iter()
+        .map(|rucksack| {
+            let c = intersection(rucksack.clone());
+            serde_json::json!({ "item": c, "priority": priority(&c) })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({ "input": rucksacks, "state": { "shared": shared } })
+}
+
+#[cfg(test)]
+mod tests {
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(157_u32));
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert_eq!(super::intersection("éaßbéc".to_string()), 'é');
+    }
+}
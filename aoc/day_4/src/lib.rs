@@ -0,0 +1,90 @@
+use aoc::{schema::{Line, Schema}, AocParse};
+
+/// Stores start and end of input range, inclusive
+#[derive(AocParse, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[aoc(format = "{0}-{1}")]
+struct Range(u32, u32);
+
+/// The two elves' ranges on one line of the input.
+#[derive(AocParse)]
+#[aoc(format = "{0},{1}")]
+struct Pair(Range, Range);
+
+impl Range {
+    fn contains(&self, other: &Self) -> bool {
+        self.0 <= other.0 && self.1 >= other.1
+    }
+}
+
+fn ranges_from_line(line: String) -> Result<(Range, Range), aoc::ParseError> {
+    let Pair(left, right) = line.parse()?;
+    Ok((left, right))
+}
+
+/// Number of pairs in which one range fully contains the other.
+pub fn part_one(path: &str) -> aoc::Answer {
+    let lines = aoc::read_as_lines(path).unwrap();
+    lines.fold(0_u32, |mut accum, line| {
+        let (left_range, right_range) = ranges_from_line(line.unwrap()).unwrap();
+        if left_range.contains(&right_range) || right_range.contains(&left_range) {
+            accum += 1;
+        }
+        accum
+    }).into()
+}
+
+/// The input's shape: one pair of section ranges per line, e.g. `2-4,6-8`,
+/// each starting no later than it ends.
+pub fn schema() -> Schema {
+    Schema::lines(Line::parse_with(|Pair(left, right)| {
+        match [left, right].into_iter().find(|range| range.0 > range.1) {
+            Some(Range(start, end)) => Err(format!("range {start}-{end} ends before it starts")),
+            None => Ok(()),
+        }
+    }))
+}
+
+/// Every pair of ranges in the input, and whether one contains the other.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let pairs = aoc::read_as_lines(path).unwrap()
+        .map(|line| ranges_from_line(line.unwrap()).unwrap())
+        .collect::<Vec<(Range, Range)>>();
+    let contained = pairs.iter()
+        .map(|(left, right)| left.contains(right) || right.contains(left))
+        .collect::<Vec<bool>>();
+
+    serde_json::json!({ "input": pairs, "state": { "contained": contained } })
+}
+
+#[cfg(test)]
+mod day_4 {
+    use super::*;
+
+    #[test]
+    fn test_range_from_string() {
+        let test_string = "1-2";
+        let test_range = test_string.parse::<Range>().unwrap();
+        assert_eq!(test_range, Range(1, 2));
+        
+        // We should be prepared for multi-digit numbers
+        let test_string = "10-20";
+        let test_range = test_string.parse::<Range>().unwrap();
+        assert_eq!(test_range, Range(10, 20));
+    }
+
+    #[test]
+    fn located_errors() {
+        let error = ranges_from_line("2-4,6-x".to_string()).unwrap_err();
+        assert_eq!(error.column, 7);
+        assert!(ranges_from_line("2-4;6-8".to_string()).unwrap_err().message.contains("expected \",\""));
+    }
+
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(2_u32));
+    }
+}
@@ -0,0 +1,190 @@
+use std::{io::BufRead, iter::Peekable};
+
+use aoc::{
+    schema::{Line, Schema, Violation},
+    AocParse, LinesIter, ParseError,
+};
+
+pub mod port;
+
+/// A rearrangement command: move `count` crates from stack `from` to `to`.
+#[derive(AocParse, Debug, PartialEq, Eq)]
+#[aoc(format = "move {count} from {from} to {to}")]
+struct Command {
+    count: u32,
+    from: usize,
+    to: usize,
+}
+
+fn parse_command(line: &str) -> Command {
+    line.parse().unwrap_or_else(|e| panic!("Invalid command {line:?}: {e}"))
+}
+
+/// Builds the port from the diagram at the top of the input, then carries out
+/// every rearrangement command listed below it.
+pub fn arrange_port(path: &str) -> port::Port {
+    arrange_port_from_lines(aoc::read_as_lines(path).unwrap())
+}
+
+/// Like `arrange_port`, but reads the input from `lines`.
+pub fn arrange_port_from_lines<R: BufRead>(lines: LinesIter<R>) -> port::Port {
+    let mut lines: Peekable<LinesIter<R>> = lines.peekable();
+
+    let mut port = port::Port::new(&mut lines);
+
+    let _ =lines.next();        // Consumes spacer line between port digram and command list
+
+    for line in lines {
+        let command = parse_command(&line.unwrap());
+        port.arrange(command.count, command.from, command.to);
+    }
+
+    port
+}
+
+/// Crates that end up on top of each stack, read left to right.
+pub fn part_one(path: &str) -> aoc::Answer {
+    arrange_port(path).top_crates().into()
+}
+
+/// The input's shape: the crate diagram with the docks numbered along its
+/// bottom, then a blank line and the rearrangement commands. Commands must
+/// name docks in the diagram, and move no more crates than a dock has.
+pub fn schema() -> Schema {
+    Schema::sections([
+        Schema::lines(Line::new(diagram_row)),
+        Schema::lines(Line::parse::<Command>()),
+    ])
+    .with(|lines| {
+        let blank = lines.iter().position(|(_, line)| line.is_empty()).unwrap();
+        let (diagram, commands) = (&lines[..blank], &lines[blank + 1..]);
+        let (bottom, numbers) = diagram[diagram.len() - 1];
+        if !is_dock_numbers(numbers) {
+            return vec![Violation::at(bottom, "expected the dock numbers below the crates")];
+        }
+        let docks = numbers.split_whitespace().count();
+        if !numbers.split_whitespace().eq((1..=docks).map(|n| n.to_string())) {
+            return vec![Violation::at(bottom, format!("expected the docks numbered 1 to {docks} in order"))];
+        }
+
+        let mut violations = vec![];
+        let (top, row) = diagram[0];
+        if (row.len() + 1) / 4 < docks {
+            violations.push(Violation::at(top, format!(
+                "the top row must reach across all {docks} docks; keep the spaces padding it",
+            )));
+        }
+        let mut heights = vec![0; docks];
+        for &(number, row) in &diagram[..diagram.len() - 1] {
+            if is_dock_numbers(row) {
+                violations.push(Violation::at(number, "dock numbers above the bottom of the diagram"));
+                continue;
+            }
+            for (i, cell) in row.as_bytes().chunks(4).enumerate() {
+                match heights.get_mut(i) {
+                    Some(height) if cell[0] == b'[' => *height += 1,
+                    None if cell[0] == b'[' => {
+                        violations.push(Violation::at(number, format!("crate beyond the last of the {docks} docks")));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for &(number, line) in commands {
+            let Command { count, from, to } = parse_command(line);
+            if let Some(dock) = [from, to].into_iter().find(|&dock| dock == 0 || dock > docks) {
+                violations.push(Violation::at(number, format!("there is no dock {dock}; they are numbered 1 to {docks}")));
+                continue;
+            }
+            let count = count as usize;
+            if heights[from - 1] < count {
+                violations.push(Violation::at(number, format!(
+                    "moves {count} crates from dock {from}, which only has {} by then", heights[from - 1],
+                )));
+            }
+            let moved = count.min(heights[from - 1]);
+            heights[from - 1] -= moved;
+            heights[to - 1] += moved;
+        }
+        violations
+    })
+}
+
+/// Whether `row` is the diagram's last, which numbers the docks. Rows of
+/// crates may start with whitespace too.
+fn is_dock_numbers(row: &str) -> bool {
+    row.trim_start().starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Checks a row of the crate diagram: crates like `[A]` and gaps, four
+/// characters apart, or the dock numbers.
+fn diagram_row(row: &str) -> Result<(), ParseError> {
+    if is_dock_numbers(row) {
+        return match row.split_whitespace().find(|n| n.parse::<usize>().is_err()) {
+            Some(n) => Err(ParseError::new(row.find(n).unwrap() + 1, format!("expected a dock number, found {n:?}"))),
+            None => Ok(()),
+        };
+    }
+    for (i, cell) in row.as_bytes().chunks(4).enumerate() {
+        let padded = cell.get(3).is_none_or(|&c| c == b' ');
+        match cell {
+            [b'[', c, b']', ..] if c.is_ascii_uppercase() && padded => {}
+            [b' ', b' ', b' ', ..] if padded => {}
+            _ => {
+                let found = String::from_utf8_lossy(cell);
+                return Err(ParseError::new(i * 4 + 1, format!("expected a crate like \"[A]\" or a gap, found {found:?}")));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The port and rearrangement commands as parsed from the input, and the port
+/// once every command has been carried out. Stacks are listed bottom to top.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let mut lines: Peekable<LinesIter> = aoc::read_as_lines(path).unwrap().peekable();
+    let initial = port::Port::new(&mut lines);
+    let commands = lines.skip(1).map(|line| parse_command(&line.unwrap())).collect::<Vec<_>>();
+
+    let mut port = initial.clone();
+    for command in &commands {
+        port.arrange(command.count, command.from, command.to);
+    }
+
+    let commands = commands.iter()
+        .map(|command| serde_json::json!({ "move": command.count, "from": command.from, "to": command.to }))
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "input": { "port": initial, "commands": commands },
+        "state": { "port": port, "top_crates": port.top_crates() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from("CMZ"));
+    }
+
+    #[test]
+    fn arrange_inline_example() {
+        let example = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n\
+            move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2\n";
+        let port = super::arrange_port_from_lines(aoc::str_as_lines(example));
+        assert_eq!(port.top_crates(), "CMZ");
+    }
+
+    #[test]
+    fn parse_command() {
+        let command = super::parse_command("move 12 from 3 to 10");
+        assert_eq!(command, super::Command { count: 12, from: 3, to: 10 });
+
+        let error = "move 1 from 2 too 3".parse::<super::Command>().unwrap_err();
+        assert_eq!(error.column, 13);
+    }
+}
@@ -1,13 +1,16 @@
 use aoc::LinesIter;
 use core::num;
+use std::io::BufRead;
 use std::iter::Peekable;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Port(Vec<Dock>);
 
 impl Port {
-    /// Creates a new port from ...some input???
-    pub fn new(lines: &mut Peekable<LinesIter>) -> Self {
+    /// Creates a new port from the crate diagram at the start of `lines`,
+    /// consuming the diagram up to and including its row of dock numbers.
+    pub fn new<R: BufRead>(lines: &mut Peekable<LinesIter<R>>) -> Self {
         let mut port = Self(Vec::new());
         let line: &String = lines.peek()    // Option<&Result<String>>
             .unwrap()       // &Result<String>
@@ -20,9 +23,9 @@ impl Port {
             let line = lines.next() // Option<Result<String>>
                 .unwrap().unwrap();
 
-            // Check if first character is whitespace, indicating 
-            // end of port diagram
-            if line.chars().nth(0).unwrap().is_whitespace() {
+            // Check if the line numbers the docks, indicating end of port
+            // diagram. Rows of crates may start with whitespace too.
+            if line.trim_start().starts_with(|c: char| c.is_ascii_digit()) {
                 break;
             }
 
@@ -49,10 +52,14 @@ impl Port {
 
     /// Prints top crate of each Dock to stout.
     pub fn print(&self) {
-        print!("Part 1: ");
-        for dock in self.0.iter() {
-            print!("{}", dock.0.last().unwrap());
-        }
+        print!("Part 1: {}", self.top_crates());
+    }
+
+    /// Collects the top crate of each Dock, left to right.
+    pub fn top_crates(&self) -> String {
+        self.0.iter()
+            .map(|dock| dock.0.last().unwrap())
+            .collect()
     }
 
     fn create_docks(&mut self, line: &String) {
@@ -69,7 +76,7 @@ impl Port {
         let mut chars = line.chars();
 
         // Get first character and push to first dock
-        let mut c = chars.nth(1).unwrap();
+        let c = chars.nth(1).unwrap();
         if c.is_alphabetic() {
             self.0[0].0.push(c);
         }
@@ -78,7 +85,8 @@ impl Port {
 
         // Grab nth character and pus
         for i in 1..num_docks {
-            c = chars.nth(3).unwrap();
+            // Editors may have trimmed the padding after the last crate
+            let Some(c) = chars.nth(3) else { break };
             if c.is_alphabetic() {
                 self.0[i].0.push(c)
             }
@@ -117,25 +125,52 @@ impl std::fmt::Display for Port {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 struct Dock(Vec<char>);
 
 #[cfg(test)]
 mod day_5 {
     use super::*;
 
+    /// The puzzle's example diagram, and the first command after it.
     fn init_lines() -> Peekable<aoc::LinesIter> {
-        aoc::read_as_lines("../inputs/day_5.txt").unwrap().peekable()
+        aoc::str_as_lines(concat!(
+            "    [D]    \n",
+            "[N] [C]    \n",
+            "[Z] [M] [P]\n",
+            " 1   2   3 \n",
+            "\n",
+            "move 1 from 2 to 1\n",
+        ))
+        .peekable()
     }
 
     #[test]
     fn new() {
-        let port = Port::new(&mut init_lines());
-        assert_eq!(port.0[0].0[0], 'H');
-        assert_eq!(*port.0[0].0.last().unwrap(), 'S');
+        let mut lines = init_lines();
+        let port = Port::new(&mut lines);
+        assert_eq!(port.0[0].0[0], 'Z');
+        assert_eq!(*port.0[0].0.last().unwrap(), 'N');
+
+        assert_eq!(port.0.last().unwrap().0[0], 'P');
+        assert_eq!(*port.0.last().unwrap().0.last().unwrap(), 'P');
+
+        // The diagram is consumed, and nothing after it
+        assert_eq!(lines.next().unwrap().unwrap(), "");
+    }
+
+    #[test]
+    fn display() {
+        let mut port = Port(vec![
+            Dock(vec!['Z', 'N']),
+            Dock(vec!['M', 'C', 'D']),
+            Dock(vec!['P']),
+        ]);
+        aoc::assert_snapshot!("display", port);
 
-        assert_eq!(port.0.last().unwrap().0[0], 'M');
-        assert_eq!(*port.0.last().unwrap().0.last().unwrap(), 'T');
+        port.arrange(3, 2, 3);
+        aoc::assert_snapshot!("display_arranged", port);
     }
 
     #[test]
@@ -0,0 +1,29 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// `find_signal_start` as it was, with a `HashSet` for each window.
+mod before {
+    use std::collections::HashSet;
+
+    pub fn find_signal_start(signal: String) -> usize {
+        let signal: Vec<char> = signal.chars().collect();
+        signal.windows(4)
+            .position(|window| window.iter().collect::<HashSet<_>>().len() == 4)
+            .unwrap() + 4
+    }
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let (signal, _) = aoc::input::read("../inputs/day_6.txt", Default::default()).unwrap();
+
+    let mut group = c.benchmark_group("find_signal_start");
+    group.bench_function("hash-set",
+        |b| b.iter(|| before::find_signal_start(signal.clone()))
+    );
+    group.bench_function("bit-set",
+        |b| b.iter(|| day_6::find_signal_start(signal.clone()))
+    );
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
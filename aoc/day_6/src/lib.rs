@@ -0,0 +1,88 @@
+use aoc::{collections::{FastHashSet, FixedBitSet}, schema::{Line, Schema}, ParseError};
+
+/// Number of characters up to the end of the first four that all differ.
+pub fn find_signal_start(signal: String) -> usize {
+    let signal: Vec<char> = signal.chars().collect();
+    
+    for (i, window) in signal.as_slice()
+        .windows(4)
+        .enumerate() 
+    {
+        if is_unique(window) {
+            return i + 4;
+        }
+    }
+    panic!("Could not find a valid signal start sequence");
+}
+
+fn is_unique(window: &[char]) -> bool {
+    assert_eq!(window.len(), 4);
+
+    // Sets only keep unique entries, so a set length of four means that all 
+    // entries were unique. Depends on the assertion that window.len() == 4.
+    if window.iter().all(char::is_ascii) {
+        // Every ASCII character fits in the set's 128 bits
+        window.iter().map(|&c| c as usize).collect::<FixedBitSet<2>>().len() == 4
+    } else {
+        window.iter().collect::<FastHashSet<_>>().len() == 4
+    }
+}
+
+/// Number of characters processed before the first start-of-packet marker.
+pub fn part_one(path: &str) -> aoc::Answer {
+    let (input, _) = aoc::input::read(path, Default::default()).unwrap();
+    find_signal_start(input).into()
+}
+
+/// The input's shape: the datastream on a single line of lowercase letters,
+/// long enough to hold a marker.
+pub fn schema() -> Schema {
+    Schema::exactly([Line::new(|line: &str| {
+        if let Some((i, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii_lowercase()) {
+            return Err(ParseError::new(i + 1, format!("expected lowercase letters, found {c:?}")));
+        }
+        if line.len() < 4 {
+            return Err(ParseError::new(1, "too short to hold a start-of-packet marker"));
+        }
+        Ok(())
+    })])
+}
+
+/// The datastream buffer, and where the first start-of-packet marker ends.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let (input, _) = aoc::input::read(path, Default::default()).unwrap();
+    let marker_end = find_signal_start(input.clone());
+
+    serde_json::json!({
+        "input": input.trim_end(),
+        "state": { "marker": &input[marker_end - 4..marker_end], "marker_end": marker_end },
+    })
+}
+
+#[cfg(test)] 
+mod day_6 {
+    use super::*;
+
+    #[test]
+    fn test_windows() {
+        let test_1 = String::from("abcdxxxxyyyyzzzz");
+        assert_eq!(find_signal_start(test_1), 4);
+
+        let test_2 = String::from("aaaabbbbccccxyz");
+        assert_eq!(find_signal_start(test_2), 15);
+
+        let test_3 = String::from("axayza");
+        assert_eq!(find_signal_start(test_3), 5);
+
+        let test_4 = String::from("ééaéßbé");
+        assert_eq!(find_signal_start(test_4), 6);
+    }
+
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(7_u32));
+    }
+}
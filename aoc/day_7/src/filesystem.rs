@@ -96,6 +96,7 @@ impl Filesystem {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 struct File {
     name: String,
     size: usize,
@@ -204,6 +205,41 @@ impl Folder {
     }
 }
 
+/// Serializes the tree from the root down. `pwd` and each folder's `parent`
+/// only point back into the tree, so they are left out.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Filesystem {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Filesystem", 2)?;
+        state.serialize_field("root", &*self.root.borrow())?;
+        state.serialize_field("total_pt_1", &self.total_pt_1)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Folder {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let children = self.children.iter()
+            .map(|child| child.borrow())
+            .collect::<Vec<_>>();
+        let children = children.iter()
+            .map(|child| &**child)
+            .collect::<Vec<&Folder>>();
+
+        let mut state = serializer.serialize_struct("Folder", 4)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("size", &self.size)?;
+        state.serialize_field("files", &self.files)?;
+        state.serialize_field("children", &children)?;
+        state.end()
+    }
+}
+
 impl Display for File {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} {}", self.name, self.size)
@@ -269,6 +305,9 @@ mod filesystem {
         fs.cd_child(&"folder_1".into());
         fs.ls_file("file_10".into(), 111);
         fs.ls_file("file_11".into(), 222);
-        println!("{}", fs);
+        aoc::assert_snapshot!("display", fs);
+
+        fs.update_all();
+        aoc::assert_snapshot!("display_sized", fs);
     }
 }
\ No newline at end of file
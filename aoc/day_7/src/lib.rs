@@ -0,0 +1,97 @@
+use aoc::*;
+use aoc::schema::{self, Line, Schema, Violation};
+
+pub mod filesystem;
+use filesystem::*;
+
+pub mod token;
+use token::Token;
+
+/// Replays the terminal output in the input to rebuild the filesystem, then
+/// calculates the size of every folder.
+pub fn build_filesystem(path: &str) -> Filesystem {
+    build_filesystem_from_lines(read_as_lines(path).unwrap())
+}
+
+/// Like `build_filesystem`, but reads the terminal output from `lines`.
+pub fn build_filesystem_from_lines<R: std::io::BufRead>(lines: LinesIter<R>) -> Filesystem {
+    let mut fs = Filesystem::new();
+
+    for line in lines {
+        let token = Token::try_from(line.unwrap()).unwrap();
+        match token {
+            Token::CdRoot => fs.cd_root(),
+            Token::CdParent => fs.cd_parent(),
+            Token::CdChild(child) => fs.cd_child(&child),
+            Token::Ls => continue,
+            Token::Dir(dir_name) => fs.ls_folder(&dir_name),
+            Token::File(file_name, file_size) => fs.ls_file(file_name, file_size),
+        }
+    }
+
+    fs.update_all();
+    fs
+}
+
+/// Sum of the sizes of all folders no larger than 100000.
+pub fn part_one(path: &str) -> Answer {
+    build_filesystem(path).total_pt_1.into()
+}
+
+/// The input's shape: terminal output, one command or listed entry per
+/// line, starting from the root folder.
+pub fn schema() -> Schema {
+    Schema::lines(Line::new(|line: &str| {
+        schema::unpadded(line)?;
+        Token::try_from(line.to_string()).map_err(|e| ParseError::new(1, e.to_string()))?;
+        Ok::<_, ParseError>(())
+    }))
+    .with(|lines| match lines[0] {
+        (_, "$ cd /") => vec![],
+        (number, _) => vec![Violation::at(number, "expected the output to start with `$ cd /`")],
+    })
+}
+
+/// The terminal output as parsed from the input, and the filesystem it
+/// describes, with every folder's size.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let tokens = read_as_lines(path).unwrap()
+        .map(|line| Token::try_from(line.unwrap()).unwrap())
+        .collect::<Vec<Token>>();
+
+    serde_json::json!({ "input": tokens, "state": build_filesystem(path) })
+}
+
+#[cfg(test)]
+mod tests {
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(95437_u32));
+    }
+
+    #[test]
+    fn inline_terminal_output() {
+        let output = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n$ cd a\n$ ls\n100 c.dat\n$ cd ..\n";
+        let fs = super::build_filesystem_from_lines(aoc::str_as_lines(output));
+        // Only `a` is small enough to count
+        assert_eq!(fs.total_pt_1, 100);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dump() {
+        let dump = super::dump(CRLF);
+        assert_eq!(dump["input"][0], "CdRoot");
+        assert_eq!(dump["input"][3], serde_json::json!({ "File": ["b.txt", 14848514] }));
+
+        let root = &dump["state"]["root"];
+        assert_eq!(root["name"], "/");
+        assert_eq!(root["size"], 48381165);
+        assert_eq!(root["children"][0]["name"], "a");
+        assert_eq!(root["children"][0]["children"][0]["files"][0], serde_json::json!({ "name": "i", "size": 584 }));
+        assert_eq!(dump["state"]["total_pt_1"], 95437);
+    }
+}
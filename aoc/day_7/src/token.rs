@@ -1,5 +1,6 @@
 /// Enumeration of possible instructions in each line of input
 /// Tokens correspond to the given filesystem APIs.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Token {
     /// Filesystem::cd_root();
     CdRoot,
@@ -30,14 +31,14 @@ impl TryFrom<String> for Token {
                 let child = child.to_string();
                 let size = size.parse::<usize>();
                 if size.is_err() {
-                    println!("{:?}", tokens);
+                    tracing::warn!(?tokens, "file size is not a number");
                     return Err(TokenParserError);
                 }
                 let size = size.unwrap();
                 Ok(Self::File(child, size))
             }
             _ => { 
-                println!("{:?}", tokens);
+                tracing::warn!(?tokens, "unexpected tokens");
                 Err(TokenParserError)
             }
 
@@ -56,4 +57,19 @@ impl std::fmt::Display for TokenParserError {
     }
 }
 
-impl std::error::Error for TokenParserError {}
\ No newline at end of file
+impl std::error::Error for TokenParserError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logs_parse_failures() {
+        let (token, logs) = aoc::logging::capture(|| {
+            Token::try_from("12kb notes.txt".to_string())
+        });
+        assert!(token.is_err());
+        assert!(logs.contains("WARN"));
+        assert!(logs.contains(r#"file size is not a number tokens=["12kb", "notes.txt"]"#));
+    }
+}
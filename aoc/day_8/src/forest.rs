@@ -3,10 +3,11 @@ use std::{
     thread
 };
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Forest(Vec<Vec<Tree>>);
 
 impl Forest {
-    pub fn new(path: &'static str) -> Self {
+    pub fn new(path: &str) -> Self {
         let lines = aoc::read_as_lines(path).unwrap();
 
         let array = lines.map(|line| {
@@ -194,6 +195,7 @@ enum Direction {
     Right
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 struct Tree {
     height: i32,
     visibility: bool,
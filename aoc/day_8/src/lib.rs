@@ -3,10 +3,13 @@ use std::{
     thread
 };
 
+use aoc::schema::Schema;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Forest(Vec<Vec<Tree>>);
 
 impl Forest {
-    pub fn new(path: &'static str) -> Self {
+    pub fn new(path: &str) -> Self {
         let lines = aoc::read_as_lines(path).unwrap();
 
         let array = lines.map(|line| {
@@ -197,6 +200,7 @@ enum Direction {
     Right
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 struct Tree {
     height: i32,
     visibility: bool,
@@ -206,4 +210,42 @@ impl Tree {
     fn new(height: i32) -> Self {
         Self {height, visibility: false}
     }
-}
\ No newline at end of file
+}
+
+/// Number of trees visible from outside the grid.
+pub fn part_one(path: &str) -> aoc::Answer {
+    let mut forest = Forest::new(path);
+    forest.calc_visibility();
+    forest.sum_visibility().into()
+}
+
+/// The input's shape: a rectangular grid of tree heights, one digit each.
+pub fn schema() -> Schema {
+    Schema::grid("digits", |c| c.is_ascii_digit())
+}
+
+/// The tree heights as parsed from the input, and which trees are visible
+/// from outside the grid.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let mut forest = Forest::new(path);
+    let heights = forest.0.iter()
+        .map(|row| row.iter().map(|tree| tree.height).collect::<Vec<i32>>())
+        .collect::<Vec<_>>();
+
+    forest.calc_visibility();
+    serde_json::json!({
+        "input": heights,
+        "state": { "forest": forest, "visible": forest.sum_visibility() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(21_u32));
+    }
+}
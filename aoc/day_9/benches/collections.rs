@@ -0,0 +1,30 @@
+use std::collections::hash_map::RandomState;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_9::StateMachine;
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let moves = aoc::read_as_lines("../inputs/day_9.txt").unwrap()
+        .map(|line| line.unwrap())
+        .collect::<Vec<String>>();
+
+    let mut group = c.benchmark_group("visited");
+    group.bench_function("sip-hash",
+        |b| b.iter(|| {
+            let mut state = StateMachine::with_hasher(RandomState::new());
+            moves.iter().for_each(|m| state.execute_line(m.clone()));
+            state.visited.len()
+        })
+    );
+    group.bench_function("fast-hash",
+        |b| b.iter(|| {
+            let mut state = StateMachine::new();
+            moves.iter().for_each(|m| state.execute_line(m.clone()));
+            state.visited.len()
+        })
+    );
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
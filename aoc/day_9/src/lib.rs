@@ -0,0 +1,382 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::BuildHasher,
+    io::BufRead,
+};
+
+use aoc::{
+    collections::FastBuildHasher,
+    schema::{self, Line, Schema},
+    simulation::{Frame, Simulation},
+    LinesIter, ParseError,
+};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+//// ANCHOR: states
+/// Positions the Head of the rope can be in, relative to the Tail.
+enum PositionH {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+    Center,
+}
+//// ANCHOR_END: states
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+//// ANCHOR: transitions
+/// Directions the Head of the rope can move, i.e. cardinal directions.
+enum MoveH {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+//// ANCHOR_END: transitions
+
+impl TryFrom<char> for MoveH {
+    type Error = InvalidCharError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value.to_ascii_uppercase() {
+            'U' => Ok(Self::Up),
+            'R' => Ok(Self::Right),
+            'D' => Ok(Self::Down),
+            'L' => Ok(Self::Left),
+            _ => Err(InvalidCharError(value))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidCharError(char);
+impl std::error::Error for InvalidCharError {}
+impl std::fmt::Display for InvalidCharError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot parse char {} as direction", self.0)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "")))]
+//// ANCHOR: state_machine
+pub struct StateMachine<S = FastBuildHasher> {
+    /// Absolute position of tail
+    abs_t: (isize, isize),      
+    /// Relative position of head
+    relative_h: PositionH,                
+    /// Set of all previously visited locations
+    pub visited: HashSet<(isize, isize), S>,   
+}
+//// ANCHOR_END: state_machine
+
+impl StateMachine {
+    pub fn new() -> Self {
+        Self::with_hasher(Default::default())
+    }
+}
+
+impl<S: BuildHasher> StateMachine<S> {
+//// ANCHOR: new
+    /// A rope whose visited positions are hashed with `hasher`.
+    pub fn with_hasher(hasher: S) -> Self {
+        let mut visited = HashSet::with_hasher(hasher);
+        visited.insert((0,0));
+        
+        Self {    
+            abs_t: (0,0),
+            relative_h: PositionH::Center,
+            visited,
+        }    
+    }
+//// ANCHOR_END: new
+
+    fn parse_line(s: String) -> (MoveH, u32) {
+        let mut t = s.split_whitespace();
+        let dir = t.next()
+        .unwrap()
+        .chars()
+        .next()
+        .unwrap()
+        .try_into()
+        .unwrap();
+        let rep = t.last().unwrap().parse().unwrap();
+        (dir, rep)
+    }
+
+    //// ANCHOR: step
+    fn step(&mut self, direction: MoveH) {
+        let (dx, dy) = match (self.relative_h, direction) {
+            (PositionH::Up, MoveH::Up) => {
+                (0, 1)
+            }
+            (PositionH::Up, MoveH::Left) => {
+                self.relative_h = PositionH::UpLeft;
+                (0,0)
+            }
+            (PositionH::Up, MoveH::Right) => {
+                self.relative_h = PositionH::UpRight;
+                (0,0)
+            }
+            (PositionH::UpRight, MoveH::Up) => {
+                self.relative_h = PositionH::Up;
+                (1,1)
+            }
+            (PositionH::UpRight, MoveH::Down) => {
+                self.relative_h = PositionH::Right;
+                (0,0)
+            }
+            (PositionH::UpRight, MoveH::Left) => {
+                self.relative_h = PositionH::Up;
+                (0,0)
+            }
+            (PositionH::UpRight, MoveH::Right) => {
+                self.relative_h = PositionH::Right;
+                (1,1)
+            }
+            (PositionH::Right, MoveH::Up) => {
+                self.relative_h = PositionH::UpRight;
+                (0,0)
+            }
+            (PositionH::Right, MoveH::Down) => {
+                self.relative_h = PositionH::DownRight;
+                (0,0)
+            }
+            (PositionH::Right, MoveH::Left) | 
+                (PositionH::Up, MoveH::Down) | 
+                (PositionH::Down, MoveH::Up) | 
+                (PositionH::Left, MoveH::Right) => 
+            {
+                self.relative_h = PositionH::Center;
+                (0,0)
+            }
+            (PositionH::Right, MoveH::Right) => {
+                (1,0)
+            }
+            (PositionH::DownRight, MoveH::Up) => {
+                self.relative_h = PositionH::Right;
+                (0,0)
+            }
+            (PositionH::DownRight, MoveH::Down) => {
+                self.relative_h = PositionH::Down;
+                (1,-1)
+            }
+            (PositionH::DownRight, MoveH::Left) => {
+                self.relative_h = PositionH::Down;
+                (0,0)
+            }
+            (PositionH::DownRight, MoveH::Right) => {
+                self.relative_h = PositionH::Right;
+                (1,-1)
+            }
+            (PositionH::Down, MoveH::Down) => {
+                (0, -1)
+            }
+            (PositionH::Down, MoveH::Left) => {
+                self.relative_h = PositionH::DownLeft;
+                (0,0)
+            }
+            (PositionH::Down, MoveH::Right) => {
+                self.relative_h = PositionH::DownRight;
+                (0,0)
+            }
+            (PositionH::DownLeft, MoveH::Up) => {
+                self.relative_h = PositionH::Left;
+                (0,0)
+            }
+            (PositionH::DownLeft, MoveH::Down) => {
+                self.relative_h = PositionH::Down;
+                (-1,-1)
+            }
+            (PositionH::DownLeft, MoveH::Left) => {
+                self.relative_h = PositionH::Left;
+                (-1,-1)
+            }
+            (PositionH::DownLeft, MoveH::Right) => {
+                self.relative_h = PositionH::Down;
+                (0,0)
+            }
+            (PositionH::Left, MoveH::Up) => {
+                self.relative_h = PositionH::UpLeft;
+                (0,0)
+            }
+            (PositionH::Left, MoveH::Down) => {
+                self.relative_h = PositionH::DownLeft;
+                (0,0)
+            }
+            (PositionH::Left, MoveH::Left) => {
+                (-1,0)
+            }
+            (PositionH::UpLeft, MoveH::Up) => {
+                self.relative_h = PositionH::Up;
+                (-1,1)
+            }
+            (PositionH::UpLeft, MoveH::Down) => {
+                self.relative_h = PositionH::Left;
+                (0,0)
+            }
+            (PositionH::UpLeft, MoveH::Left) => {
+                self.relative_h = PositionH::Left;
+                (-1,1)
+            }
+            (PositionH::UpLeft, MoveH::Right) => {
+                self.relative_h = PositionH::Up;
+                (0,0)
+            }
+            (PositionH::Center, MoveH::Up) => {
+                self.relative_h = PositionH::Up;
+                (0,0)
+            }
+            (PositionH::Center, MoveH::Down) => {
+                self.relative_h = PositionH::Down;
+                (0,0)
+            }
+            (PositionH::Center, MoveH::Left) => {
+                self.relative_h = PositionH::Left;
+                (0,0)
+            }
+            (PositionH::Center, MoveH::Right) => {
+                self.relative_h = PositionH::Right;
+                (0,0)
+            }
+        };
+        let (x, y) = self.abs_t;
+        self.abs_t = (x + dx, y + dy);
+        self.visited.insert(self.abs_t.clone());
+        tracing::trace!(?direction, head = ?self.relative_h, tail = ?self.abs_t, "step");
+    }
+    //// ANCHOR_END: step
+
+    pub fn execute_line(&mut self, s: String) {
+        let (direction, num_steps) = Self::parse_line(s);
+        for _ in 0..num_steps {
+            self.step(direction);
+        }
+    }
+}
+
+/// The rope following the input's moves, one step of the head per tick.
+pub struct Rope<S = FastBuildHasher> {
+    pub state: StateMachine<S>,
+    /// Steps of the head still to take, in order
+    steps: VecDeque<MoveH>,
+}
+
+impl Rope {
+    /// A rope at the origin, about to make every move in the input.
+    pub fn new(path: &str) -> Self {
+        Self::from_lines(aoc::read_as_lines(path).unwrap())
+    }
+
+    /// Like `new`, but reads the moves from `lines`.
+    pub fn from_lines<R: BufRead>(lines: LinesIter<R>) -> Self {
+        let steps = lines
+            .flat_map(|line| {
+                let (direction, num_steps) = <StateMachine>::parse_line(line.unwrap());
+                std::iter::repeat_n(direction, num_steps as usize)
+            })
+            .collect();
+        Self { state: StateMachine::new(), steps }
+    }
+}
+
+impl<S: BuildHasher> Simulation for Rope<S> {
+    fn step(&mut self) {
+        if let Some(direction) = self.steps.pop_front() {
+            self.state.step(direction);
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Every position the tail has visited (color 1) and where it is now
+    /// (color 2), north up, cropped to the positions visited so far.
+    fn render(&self) -> Option<Frame> {
+        let visited = &self.state.visited;
+        let (min_x, max_x) = (visited.iter().map(|p| p.0).min()?, visited.iter().map(|p| p.0).max()?);
+        let (min_y, max_y) = (visited.iter().map(|p| p.1).min()?, visited.iter().map(|p| p.1).max()?);
+
+        let mut frame = Frame::new((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+        let mut draw = |(x, y): (isize, isize), color| frame.set((x - min_x) as usize, (max_y - y) as usize, color);
+        for &position in visited {
+            draw(position, 1);
+        }
+        draw(self.state.abs_t, 2);
+        Some(frame)
+    }
+}
+
+/// Number of positions the tail of the rope visits at least once.
+pub fn part_one(path: &str) -> aoc::Answer {
+    let mut rope = Rope::new(path);
+    rope.run();
+    rope.state.visited.len().into()
+}
+
+/// The input's shape: one move of the head per line, a direction (`U`, `D`,
+/// `L` or `R`) then a space and a number of steps.
+pub fn schema() -> Schema {
+    Schema::lines(Line::new(|line: &str| {
+        schema::unpadded(line)?;
+        let Some((direction, steps)) = line.split_once(' ') else {
+            return Err(ParseError::new(1, format!("expected a move like \"R 4\", found {line:?}")));
+        };
+        direction.parse::<char>().ok()
+            .and_then(|c| MoveH::try_from(c).ok())
+            .ok_or_else(|| ParseError::new(1, format!("expected a direction U, D, L or R, found {direction:?}")))?;
+        steps.parse::<u32>()
+            .map_err(|e| ParseError::new(direction.chars().count() + 2, format!("invalid steps {steps:?}: {e}")))?;
+        Ok(())
+    }))
+}
+
+/// Every move as parsed from the input, and the rope once they are all made:
+/// where the tail is, where the head is relative to it, and every position the
+/// tail has visited.
+#[cfg(feature = "serde")]
+pub fn dump(path: &str) -> serde_json::Value {
+    let lines = aoc::read_as_lines(path).unwrap();
+
+    let mut moves = vec![];
+    let mut state = StateMachine::new();
+    for line in lines {
+        let (direction, num_steps) = <StateMachine>::parse_line(line.unwrap());
+        for _ in 0..num_steps {
+            state.step(direction);
+        }
+        moves.push((direction, num_steps));
+    }
+
+    serde_json::json!({ "input": moves, "state": state })
+}
+
+#[cfg(test)]
+mod tests {
+    const CRLF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/example_crlf.txt");
+
+    #[test]
+    fn crlf_example() {
+        assert_eq!(super::part_one(CRLF), aoc::Answer::from(13_u32));
+    }
+
+    #[test]
+    fn renders_the_tail() {
+        use aoc::simulation::Driver;
+
+        let mut rope = super::Rope::from_lines(aoc::str_as_lines("R 3\nU 2\n"));
+        let mut frames = vec![];
+        let outcome = Driver::new()
+            .on_frame(|_, frame| frames.push(frame.to_text(&['.', '#', 'T'])))
+            .run(&mut rope);
+        assert_eq!((outcome.ticks, outcome.done), (5, true));
+        assert_eq!(frames.last().unwrap(), &["...T", "###."]);
+    }
+}
@@ -0,0 +1,70 @@
+//! Gives each day a version that changes whenever its source, the shared
+//! library's or derive macros', or the locked dependency versions do, as
+//! `AOC_DAY_<N>_VERSION`. The answer cache uses it to tell when a remembered
+//! answer may be out of date.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+
+fn main() {
+    let workspace = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("..");
+
+    let mut shared = Sha256::new();
+    let shared_paths = [
+        workspace.join("src"),
+        workspace.join("cargo.toml"),
+        workspace.join("Cargo.lock"),
+        workspace.join("aoc_derive/src"),
+        workspace.join("aoc_derive/Cargo.toml"),
+    ];
+    for path in shared_paths {
+        hash(&mut shared, &path);
+    }
+
+    let mut days = fs::read_dir(&workspace).unwrap()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_prefix("day_")?.parse::<u8>().ok()
+        })
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+
+    for day in days {
+        let dir = workspace.join(format!("day_{day}"));
+        let mut hasher = shared.clone();
+        for path in [dir.join("src"), dir.join("Cargo.toml")] {
+            hash(&mut hasher, &path);
+        }
+        let version = hasher.finalize().iter().take(8).map(|byte| format!("{byte:02x}")).collect::<String>();
+        println!("cargo:rustc-env=AOC_DAY_{day}_VERSION={version}");
+    }
+}
+
+/// Feeds the file at `path`, or every file under it, to `hasher`, names
+/// included, and has the build rerun when any of them change.
+fn hash(hasher: &mut Sha256, path: &Path) {
+    println!("cargo:rerun-if-changed={}", path.display());
+    let mut files = vec![];
+    collect(path, &mut files);
+    files.sort();
+    for file in files {
+        hasher.update(file.strip_prefix(path).unwrap_or(&file).to_string_lossy().as_bytes());
+        hasher.update(fs::read(&file).unwrap_or_default());
+    }
+}
+
+fn collect(path: &Path, files: &mut Vec<PathBuf>) {
+    match fs::read_dir(path) {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                collect(&entry.path(), files);
+            }
+        }
+        Err(_) if path.is_file() => files.push(path.to_path_buf()),
+        Err(_) => {}
+    }
+}
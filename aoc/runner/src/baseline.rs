@@ -0,0 +1,451 @@
+use std::{
+    fs, io,
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{counters::Counts, report};
+
+/// How many median absolute deviations a change must exceed before it counts
+/// as real rather than noise.
+pub const NOISE_FACTOR: f64 = 3.0;
+
+/// The middle of a set of measurements, and how far they stray from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spread {
+    pub median: u64,
+    /// Median absolute deviation from the median, a measure of the noise
+    /// that single outliers do not skew.
+    pub mad: u64,
+}
+
+impl Spread {
+    /// Summarises `values`, which must not be empty.
+    pub fn of(values: &[u64]) -> Self {
+        let median = median(values);
+        let deviations = values.iter().map(|value| value.abs_diff(median)).collect::<Vec<_>>();
+        Self { median, mad: self::median(&deviations) }
+    }
+}
+
+fn median(values: &[u64]) -> u64 {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    }
+}
+
+/// Timing of one part of one day over several runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    pub day: u8,
+    pub part: u8,
+    /// Name of the external solver timed, or `None` for a built-in one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solver: Option<String>,
+    pub median_ns: u64,
+    /// Median absolute deviation of the run times. See `Spread`.
+    pub mad_ns: u64,
+    pub samples: usize,
+    /// Instructions retired per run, if performance counters were used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<Spread>,
+    /// Cache misses per run, if the CPU counts them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_misses: Option<Spread>,
+    /// SHA-256 of the input timed, see `cache::input_hash`. Only timings of
+    /// the same input are compared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+}
+
+impl Timing {
+    /// Summarises `samples`, which must not be empty.
+    pub fn from_samples(day: u8, part: u8, samples: &[Duration]) -> Self {
+        let nanos = samples.iter().map(|sample| sample.as_nanos() as u64).collect::<Vec<_>>();
+        let time = Spread::of(&nanos);
+        Self {
+            day,
+            part,
+            solver: None,
+            median_ns: time.median,
+            mad_ns: time.mad,
+            samples: samples.len(),
+            instructions: None,
+            cache_misses: None,
+            input: None,
+        }
+    }
+
+    /// Adds the counts from each run, which must not be empty.
+    pub fn with_counts(mut self, counts: &[Counts]) -> Self {
+        let instructions = counts.iter().map(|counts| counts.instructions).collect::<Vec<_>>();
+        self.instructions = Some(Spread::of(&instructions));
+        self.cache_misses = counts.iter()
+            .map(|counts| counts.cache_misses)
+            .collect::<Option<Vec<_>>>()
+            .map(|misses| Spread::of(&misses));
+        self
+    }
+
+    pub fn median(&self) -> Duration {
+        Duration::from_nanos(self.median_ns)
+    }
+
+    /// What a timing is recorded under in a baseline.
+    fn key(&self) -> (u8, u8, Option<&str>, Option<&str>) {
+        (self.day, self.part, self.solver.as_deref(), self.input.as_deref())
+    }
+
+    fn time(&self) -> Spread {
+        Spread { median: self.median_ns, mad: self.mad_ns }
+    }
+}
+
+/// The timings of every benchmarked part at one commit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub commit: String,
+    /// Seconds since the Unix epoch
+    pub recorded: u64,
+    pub timings: Vec<Timing>,
+}
+
+impl Baseline {
+    pub fn new(commit: &str, timings: Vec<Timing>) -> Self {
+        let recorded = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Self { commit: commit.to_string(), recorded, timings }
+    }
+
+    /// The timing of the same day, part and solver as `timing`, on the same
+    /// input.
+    pub fn matching(&self, timing: &Timing) -> Option<&Timing> {
+        self.timings.iter().find(|recorded| recorded.key() == timing.key())
+    }
+}
+
+/// Baselines recorded on this machine, kept as JSON. Timings from different
+/// machines are not comparable, so the store is not checked in.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Store {
+    baselines: Vec<Baseline>,
+}
+
+impl Store {
+    /// Loads the store at `path`, or an empty one if there is no file yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("Invalid baselines in {}: {e}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Could not read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).unwrap();
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, json))
+            .map_err(|e| format!("Could not write {}: {e}", path.display()))
+    }
+
+    /// Adds `baseline`, replacing the timings of any day, part and solver
+    /// already recorded at the same commit on the same input.
+    pub fn record(&mut self, baseline: Baseline) {
+        match self.baselines.iter_mut().find(|existing| existing.commit == baseline.commit) {
+            Some(existing) => {
+                existing.recorded = baseline.recorded;
+                for timing in baseline.timings {
+                    existing.timings.retain(|t| t.key() != timing.key());
+                    existing.timings.push(timing);
+                }
+                existing.timings.sort_by(|a, b| a.key().cmp(&b.key()));
+            }
+            None => self.baselines.push(baseline),
+        }
+    }
+
+    /// The baseline whose commit starts with `commit`, so short hashes work.
+    pub fn find(&self, commit: &str) -> Option<&Baseline> {
+        self.baselines.iter().find(|baseline| baseline.commit.starts_with(commit))
+    }
+
+    /// The most recently recorded baseline from a commit other than `commit`.
+    pub fn latest_except(&self, commit: &str) -> Option<&Baseline> {
+        self.baselines.iter()
+            .filter(|baseline| baseline.commit != commit)
+            .max_by_key(|baseline| baseline.recorded)
+    }
+}
+
+/// The commit the workspace is at, with `-dirty` appended if there are
+/// uncommitted changes. `None` outside a git checkout.
+pub fn current_commit() -> Option<String> {
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let clean = Command::new("git")
+        .args(["diff", "--quiet", "HEAD"])
+        .current_dir(dir)
+        .status()
+        .is_ok_and(|status| status.success());
+    Some(if clean { commit } else { format!("{commit}-dirty") })
+}
+
+/// When a slowdown is reported, as percentages over the baseline median.
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+    pub warn: f64,
+    pub fail: f64,
+}
+
+/// How a part's timing compares to its baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Nothing to compare against
+    New,
+    /// Within the noise or the thresholds
+    Unchanged,
+    Improved,
+    /// Slower by more than the warning threshold
+    Slower,
+    /// Slower by more than the failure threshold
+    Regressed,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::New => write!(f, "new"),
+            Self::Unchanged => write!(f, "ok"),
+            Self::Improved => write!(f, "improved"),
+            Self::Slower => write!(f, "warning: slower"),
+            Self::Regressed => write!(f, "regressed"),
+        }
+    }
+}
+
+/// One part's current timing next to its baseline.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub current: Timing,
+    pub baseline: Option<Timing>,
+    pub verdict: Verdict,
+}
+
+impl Comparison {
+    /// What the parts are compared by: instruction counts where both runs
+    /// have them, as they barely vary from run to run, otherwise time.
+    fn measures(&self) -> Option<(Spread, Spread)> {
+        let baseline = self.baseline.as_ref()?;
+        match (self.current.instructions, baseline.instructions) {
+            (Some(current), Some(baseline)) => Some((current, baseline)),
+            _ => Some((self.current.time(), baseline.time())),
+        }
+    }
+
+    /// Whether the comparison is by instruction count rather than time.
+    pub fn by_instructions(&self) -> bool {
+        self.baseline.as_ref()
+            .is_some_and(|baseline| self.current.instructions.is_some() && baseline.instructions.is_some())
+    }
+
+    /// Percentage change from the baseline.
+    pub fn change(&self) -> Option<f64> {
+        let (current, baseline) = self.measures()?;
+        let base = baseline.median.max(1) as f64;
+        Some((current.median as f64 - base) / base * 100.0)
+    }
+}
+
+/// Compares `current` against `baseline`. A change only counts if it is
+/// beyond both the threshold and `NOISE_FACTOR` times the combined spread of
+/// the two runs, so noisy days do not raise false alarms.
+pub fn compare(current: &Timing, baseline: Option<&Timing>, thresholds: Thresholds) -> Comparison {
+    let mut comparison = Comparison {
+        current: current.clone(),
+        baseline: baseline.cloned(),
+        verdict: Verdict::New,
+    };
+    let (Some((current, baseline)), Some(change)) = (comparison.measures(), comparison.change()) else {
+        return comparison;
+    };
+
+    let difference = current.median.abs_diff(baseline.median) as f64;
+    let noise = NOISE_FACTOR * (current.mad + baseline.mad) as f64;
+    comparison.verdict = if difference <= noise {
+        Verdict::Unchanged
+    } else if change > thresholds.fail {
+        Verdict::Regressed
+    } else if change > thresholds.warn {
+        Verdict::Slower
+    } else if change < -thresholds.warn {
+        Verdict::Improved
+    } else {
+        Verdict::Unchanged
+    };
+    comparison
+}
+
+/// Renders comparisons as a plain text table, one row per part. The baseline
+/// is shown in whatever the parts were compared by.
+pub fn table(comparisons: &[Comparison]) -> String {
+    let count = |spread: Option<Spread>| spread.map_or_else(|| "-".to_string(), |spread| spread.median.to_string());
+    let rows = comparisons.iter()
+        .map(|comparison| {
+            let current = &comparison.current;
+            let spread = Duration::from_nanos(current.mad_ns);
+            let baseline = match &comparison.baseline {
+                None => "-".to_string(),
+                Some(baseline) if comparison.by_instructions() => count(baseline.instructions),
+                Some(baseline) => format!("{:.2?}", baseline.median()),
+            };
+            [
+                current.day.to_string(),
+                current.part.to_string(),
+                report::solver_cell(current.solver.as_deref()),
+                format!("{:.2?} ± {:.2?}", current.median(), spread),
+                count(current.instructions),
+                count(current.cache_misses),
+                baseline,
+                comparison.change().map_or_else(|| "-".to_string(), |change| format!("{change:+.1}%")),
+                comparison.verdict.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    if comparisons.iter().all(|comparison| comparison.current.solver.is_none()) {
+        let rows = rows.into_iter()
+            .map(|[day, part, _, median, instructions, misses, baseline, change, verdict]| {
+                [day, part, median, instructions, misses, baseline, change, verdict]
+            })
+            .collect::<Vec<_>>();
+        let header = ["Day", "Part", "Median", "Instructions", "Cache misses", "Baseline", "Change", "Verdict"];
+        return report::render(header, &rows, 2);
+    }
+    let header = ["Day", "Part", "Solver", "Median", "Instructions", "Cache misses", "Baseline", "Change", "Verdict"];
+    report::render(header, &rows, 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLDS: Thresholds = Thresholds { warn: 5.0, fail: 15.0 };
+
+    fn timing(median_ns: u64, mad_ns: u64) -> Timing {
+        Timing {
+            day: 3,
+            part: 1,
+            solver: None,
+            median_ns,
+            mad_ns,
+            samples: 10,
+            instructions: None,
+            cache_misses: None,
+            input: Some("5e4d3c2b".to_string()),
+        }
+    }
+
+    #[test]
+    fn summarise_samples() {
+        let samples = [10, 12, 11, 500, 13].map(Duration::from_micros);
+        let timing = Timing::from_samples(6, 1, &samples);
+        assert_eq!(timing.median(), Duration::from_micros(12));
+        // The outlier barely moves the spread
+        assert_eq!(timing.mad_ns, 1_000);
+        assert_eq!(timing.samples, 5);
+
+        let timing = Timing::from_samples(6, 1, &[10, 20].map(Duration::from_micros));
+        assert_eq!(timing.median(), Duration::from_micros(15));
+
+        let counts = [
+            Counts { instructions: 1000, cache_misses: Some(4) },
+            Counts { instructions: 1002, cache_misses: Some(6) },
+            Counts { instructions: 1001, cache_misses: None },
+        ];
+        let timing = timing.with_counts(&counts);
+        assert_eq!(timing.instructions, Some(Spread { median: 1001, mad: 1 }));
+        // Only some runs counted cache misses, so they are left out
+        assert_eq!(timing.cache_misses, None);
+    }
+
+    #[test]
+    fn verdicts() {
+        let base = timing(1_000_000, 10_000);
+        let verdict = |current| compare(&current, Some(&base), THRESHOLDS).verdict;
+
+        assert_eq!(verdict(timing(1_030_000, 10_000)), Verdict::Unchanged);
+        assert_eq!(verdict(timing(1_100_000, 10_000)), Verdict::Slower);
+        assert_eq!(verdict(timing(1_200_000, 10_000)), Verdict::Regressed);
+        assert_eq!(verdict(timing(800_000, 10_000)), Verdict::Improved);
+        // A 20% slowdown on a run this noisy is not conclusive
+        assert_eq!(verdict(timing(1_200_000, 100_000)), Verdict::Unchanged);
+
+        // Instruction counts win over time when both runs have them
+        let counted = |instructions| Timing {
+            instructions: Some(Spread { median: instructions, mad: 0 }),
+            ..timing(1_000_000, 10_000)
+        };
+        let comparison = compare(&counted(1_100_000), Some(&counted(1_000_000)), THRESHOLDS);
+        assert!(comparison.by_instructions());
+        assert_eq!(comparison.verdict, Verdict::Slower);
+        let comparison = compare(&counted(1_100_000), Some(&base), THRESHOLDS);
+        assert!(!comparison.by_instructions());
+        assert_eq!(comparison.verdict, Verdict::Unchanged);
+
+        let new = compare(&base, None, THRESHOLDS);
+        assert_eq!(new.verdict, Verdict::New);
+        assert_eq!(new.change(), None);
+    }
+
+    #[test]
+    fn store_round_trip() {
+        let path = std::env::temp_dir().join("runner_baseline_store_round_trip.json");
+        let _ = fs::remove_file(&path);
+
+        let mut store = Store::load(&path).unwrap();
+        let mut old = Baseline::new("abc123", vec![timing(100, 1)]);
+        old.recorded = 1;
+        store.record(old);
+        let mut new = Baseline::new("def456", vec![timing(200, 2)]);
+        new.recorded = 2;
+        store.record(new);
+        store.save(&path).unwrap();
+
+        let mut store = Store::load(&path).unwrap();
+        assert_eq!(store.find("abc").unwrap().matching(&timing(0, 0)), Some(&timing(100, 1)));
+        let alice = Timing { solver: Some("alice".to_string()), ..timing(0, 0) };
+        assert_eq!(store.find("abc").unwrap().matching(&alice), None);
+        assert_eq!(store.latest_except("xyz").unwrap().commit, "def456");
+        assert_eq!(store.latest_except("def456").unwrap().commit, "abc123");
+
+        // Recording the same commit again replaces its timings
+        store.record(Baseline::new("abc123", vec![timing(150, 1)]));
+        assert_eq!(store.find("abc123").unwrap().timings, [timing(150, 1)]);
+
+        // but timings of another input are kept beside them, and not compared
+        let example = Timing { input: Some("e3b0c442".to_string()), ..timing(10, 1) };
+        store.record(Baseline::new("abc123", vec![example.clone()]));
+        let baseline = store.find("abc123").unwrap();
+        assert_eq!(baseline.timings.len(), 2);
+        assert_eq!(baseline.matching(&timing(0, 0)), Some(&timing(150, 1)));
+        assert_eq!(baseline.matching(&example), Some(&example));
+        assert_eq!(store.latest_except("def456").unwrap().commit, "abc123");
+
+        fs::write(&path, "not json").unwrap();
+        assert!(Store::load(&path).unwrap_err().contains("Invalid baselines"));
+    }
+}
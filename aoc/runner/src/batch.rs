@@ -0,0 +1,303 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use aoc::Answer;
+
+use crate::{
+    execute::{self, Timeouts},
+    registry::Solution,
+    report::{self, Outcome, Status},
+};
+
+/// Extension of the file holding the expected answers for an input, e.g.
+/// `alice.expected` for `alice.txt`.
+pub const SIDECAR_EXTENSION: &str = "expected";
+
+/// Expected answers for one input, by part. Parsed from a sidecar file with a
+/// line per part, such as:
+///
+/// ```text
+/// # Alice's input
+/// 1: 24000
+/// 2: 45000
+/// ```
+///
+/// Blank lines and lines starting with `#` are ignored. Picture answers are
+/// written as the letters they spell.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Expected(BTreeMap<u8, String>);
+
+impl Expected {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let part = line.split_once(':')
+                .and_then(|(part, answer)| Some((part.trim().parse::<u8>().ok()?, answer.trim())));
+            match part {
+                Some((part, answer)) => answers.insert(part, answer.to_string()),
+                None => return Err(format!("line {}: expected `<part>: <answer>`, found {line:?}", i + 1)),
+            };
+        }
+        Ok(Self(answers))
+    }
+
+    /// Reads the sidecar at `path`. A missing sidecar expects nothing.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("Invalid sidecar {}: {e}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Could not read {}: {e}", path.display())),
+        }
+    }
+
+    /// Whether `answer` is the expected one for `part`. `None` if no answer
+    /// is expected for that part.
+    pub fn check(&self, part: u8, answer: &Answer) -> Option<bool> {
+        let expected = self.0.get(&part)?;
+        let read = answer.clone().read_with(aoc::answer::ocr);
+        Some(answer.to_string() == *expected || read.to_string() == *expected)
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        self.0.get(&part).map(String::as_str)
+    }
+}
+
+/// How an answer compared against the sidecar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// There is no expected answer for this part
+    Unchecked,
+    Correct,
+    Wrong,
+}
+
+/// The result of running one part of the chosen day on one input file.
+#[derive(Debug)]
+pub struct Run {
+    /// Input file name, relative to the batch directory
+    pub file: String,
+    pub outcome: Outcome,
+    pub expected: Option<String>,
+    pub verdict: Verdict,
+}
+
+impl Run {
+    pub fn is_ok(&self) -> bool {
+        self.outcome.is_ok() && self.verdict != Verdict::Wrong
+    }
+}
+
+/// Input files in `dir`, sorted by name. Sidecars and hidden files are skipped.
+pub fn inputs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut inputs = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        let sidecar = path.extension().is_some_and(|extension| extension == SIDECAR_EXTENSION);
+        if path.is_file() && !hidden && !sidecar {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Runs every solution (the parts of one day) on every input in `dir`, on
+/// `jobs` worker threads, and checks the answers against any sidecars.
+/// Runs are returned sorted by file, then part, then solver.
+pub fn run(solutions: &[Solution], dir: &Path, jobs: usize, timeouts: &Timeouts) -> Result<Vec<Run>, String> {
+    let inputs = inputs(dir).map_err(|e| format!("Could not list {}: {e}", dir.display()))?;
+
+    let mut expected = BTreeMap::new();
+    let mut runs = vec![];
+    for input in inputs {
+        let file = input.strip_prefix(dir).unwrap_or(&input).to_string_lossy().to_string();
+
+        let answers = Expected::load(&input.with_extension(SIDECAR_EXTENSION))?;
+        expected.insert(file.clone(), answers);
+
+        for solution in solutions {
+            runs.push((file.clone(), solution.clone(), input.clone()));
+        }
+    }
+
+    let mut runs = execute::solve_many(runs, jobs, timeouts).into_iter()
+        .map(|(file, outcome)| {
+            let answers = &expected[&file];
+            let verdict = match outcome.answer.as_ref().and_then(|answer| answers.check(outcome.part, answer)) {
+                None => Verdict::Unchecked,
+                Some(true) => Verdict::Correct,
+                Some(false) => Verdict::Wrong,
+            };
+            Run {
+                expected: answers.get(outcome.part).map(str::to_string),
+                file,
+                outcome,
+                verdict,
+            }
+        })
+        .collect::<Vec<_>>();
+    runs.sort_by(|a, b| {
+        (&a.file, a.outcome.part, &a.outcome.solver).cmp(&(&b.file, b.outcome.part, &b.outcome.solver))
+    });
+
+    Ok(runs)
+}
+
+/// Renders runs as a plain text table, one row per file and part, with a
+/// column for the solver if any external ones ran.
+pub fn table(runs: &[Run]) -> String {
+    let rows = runs.iter()
+        .map(|run| {
+            let status = match (&run.verdict, run.outcome.is_ok()) {
+                (_, false) => run.outcome.status.to_string(),
+                (Verdict::Wrong, true) => "wrong answer".to_string(),
+                (Verdict::Correct, true) => "ok".to_string(),
+                (Verdict::Unchecked, true) => "ok (unchecked)".to_string(),
+            };
+            [
+                run.file.clone(),
+                run.outcome.part.to_string(),
+                report::solver_cell(run.outcome.solver.as_deref()),
+                report::answer_cell(run.outcome.answer.as_ref()),
+                run.expected.clone().unwrap_or_else(|| "-".to_string()),
+                format!("{:.2?}", run.outcome.elapsed),
+                status,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    if runs.iter().all(|run| run.outcome.solver.is_none()) {
+        let rows = rows.into_iter()
+            .map(|[file, part, _, answer, expected, time, status]| [file, part, answer, expected, time, status])
+            .collect::<Vec<_>>();
+        return report::render(["File", "Part", "Answer", "Expected", "Time", "Status"], &rows, 0);
+    }
+    report::render(["File", "Part", "Solver", "Answer", "Expected", "Time", "Status"], &rows, 0)
+}
+
+/// One line tally of the runs, e.g.
+/// `6 runs: 4 correct, 1 wrong, 0 failed, 0 timed out, 1 unchecked`.
+pub fn summary(runs: &[Run]) -> String {
+    let count = |f: fn(&Run) -> bool| runs.iter().filter(|run| f(run)).count();
+    let timed_out = |run: &Run| matches!(run.outcome.status, Status::TimedOut(_));
+    format!(
+        "{} runs: {} correct, {} wrong, {} failed, {} timed out, {} unchecked",
+        runs.len(),
+        count(|run| run.outcome.is_ok() && run.verdict == Verdict::Correct),
+        count(|run| run.outcome.is_ok() && run.verdict == Verdict::Wrong),
+        count(|run| !run.outcome.is_ok() && !matches!(run.outcome.status, Status::TimedOut(_))),
+        count(timed_out),
+        count(|run| run.outcome.is_ok() && run.verdict == Verdict::Unchecked),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::Limits;
+
+    #[test]
+    fn parse_expected() {
+        let expected = Expected::parse("# Alice\n1: 24000\n\n 2 :  HELP \n").unwrap();
+        assert_eq!(expected.get(1), Some("24000"));
+        assert_eq!(expected.get(2), Some("HELP"));
+        assert_eq!(expected.get(3), None);
+
+        assert!(Expected::parse("1 24000").unwrap_err().contains("line 1"));
+        assert!(Expected::parse("one: 24000").is_err());
+    }
+
+    #[test]
+    fn check_answers() {
+        let expected = Expected::parse("1: 24000\n2: HELP").unwrap();
+        assert_eq!(expected.check(1, &24000_u32.into()), Some(true));
+        assert_eq!(expected.check(1, &"24001".into()), Some(false));
+        assert_eq!(expected.check(3, &1_u32.into()), None);
+
+        // Pictures are compared by the letters they spell
+        let screen = [
+            "#..#.####.#....###..",
+            "#..#.#....#....#..#.",
+            "####.###..#....#..#.",
+            "#..#.#....#....###..",
+            "#..#.#....#....#....",
+            "#..#.####.####.#....",
+        ];
+        let screen = Answer::Grid(screen.iter().map(|row| row.to_string()).collect());
+        assert_eq!(expected.check(2, &screen), Some(true));
+    }
+
+    #[test]
+    fn run_directory() {
+        let dir = std::env::temp_dir().join("runner_batch_run_directory");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("alice.txt"), "12").unwrap();
+        fs::write(dir.join("alice.expected"), "1: 12\n2: 24").unwrap();
+        fs::write(dir.join("bob.txt"), "7").unwrap();
+        fs::write(dir.join("bob.expected"), "1: 8").unwrap();
+        fs::write(dir.join("carol.txt"), "").unwrap();
+        fs::write(dir.join(".notes"), "not an input").unwrap();
+
+        // Part one echoes the input, part two doubles it
+        let solutions = [
+            Solution::new(1, 1, |path| fs::read_to_string(path).unwrap().into()),
+            Solution::new(1, 2, |path| {
+                let n = fs::read_to_string(path).unwrap().parse::<u32>().unwrap();
+                (n * 2).into()
+            }),
+        ];
+
+        let runs = run(&solutions, &dir, 2, &Timeouts::default()).unwrap();
+        let summary = runs.iter()
+            .map(|run| (run.file.as_str(), run.outcome.part, run.verdict.clone(), run.outcome.is_ok()))
+            .collect::<Vec<_>>();
+        assert_eq!(summary, [
+            ("alice.txt", 1, Verdict::Correct, true),
+            ("alice.txt", 2, Verdict::Correct, true),
+            ("bob.txt", 1, Verdict::Wrong, true),
+            ("bob.txt", 2, Verdict::Unchecked, true),
+            ("carol.txt", 1, Verdict::Unchecked, true),
+            ("carol.txt", 2, Verdict::Unchecked, false),
+        ]);
+        assert_eq!(super::summary(&runs), "6 runs: 2 correct, 1 wrong, 1 failed, 0 timed out, 2 unchecked");
+
+        let table = table(&runs);
+        assert!(table.starts_with("File       Part  Answer  Expected  Time"));
+        assert!(table.contains("\nbob.txt    1     7       8  "));
+        assert!(table.lines().nth(4).unwrap().ends_with("wrong answer"));
+    }
+
+    #[test]
+    fn run_external_solver() {
+        let dir = std::env::temp_dir().join("runner_batch_run_external_solver");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("alice.txt"), "12").unwrap();
+        fs::write(dir.join("alice.expected"), "1: 12").unwrap();
+
+        // The same answer from a built-in and an external solver. Folders in
+        // the batch folder are not inputs, so the solver can live in one.
+        fs::create_dir_all(dir.join("solvers")).unwrap();
+        fs::write(dir.join("solvers/echo.sh"), "printf '1: '; cat").unwrap();
+        let mut solutions = crate::external::parse("echo 1 1 sh echo.sh", &dir.join("solvers"), Limits::default())
+            .unwrap();
+        solutions.push(Solution::new(1, 1, |path| fs::read_to_string(path).unwrap().into()));
+
+        let runs = run(&solutions, &dir, 2, &Timeouts::default()).unwrap();
+        assert!(runs.iter().all(|run| run.verdict == Verdict::Correct), "{runs:?}");
+        let solvers = runs.iter().map(|run| run.outcome.solver.as_deref()).collect::<Vec<_>>();
+        assert_eq!(solvers, [None, Some("echo")]);
+        assert!(table(&runs).starts_with("File       Part  Solver    Answer"));
+    }
+}
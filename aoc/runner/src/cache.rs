@@ -0,0 +1,272 @@
+//! Answers remembered between runs, so days whose code and input have not
+//! changed are answered without being run again. An answer is keyed by the
+//! day, part, a hash of the input and the solution's version (see
+//! `Solution::version`). Only built-in solutions that finished are cached.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use aoc::Answer;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    registry::Solution,
+    report::{self, Outcome, Status},
+};
+
+/// One remembered answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    /// SHA-256 of the input, in hex
+    pub input: String,
+    pub version: String,
+    pub answer: Answer,
+    /// How long the run that found the answer took
+    pub elapsed_ns: u64,
+    /// When it ran, in seconds since the Unix epoch
+    pub recorded: u64,
+}
+
+/// What an answer is remembered by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub version: &'static str,
+}
+
+impl Key {
+    /// The key for running `solution` on the input at `input`, or `None` if
+    /// the solution cannot be cached or the input cannot be read.
+    pub fn new(solution: &Solution, input: &Path) -> Option<Self> {
+        let version = solution.version()?;
+        let input = input_hash(input)?;
+        Some(Self { day: solution.day, part: solution.part, input, version })
+    }
+}
+
+/// The SHA-256 of the input at `path`, in hex, or `None` if it cannot be read.
+pub fn input_hash(path: &Path) -> Option<String> {
+    let input = fs::read(path).ok()?;
+    Some(Sha256::digest(input).iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cache {
+    pub entries: Vec<Entry>,
+}
+
+impl Cache {
+    /// Loads the cache at `path`, or an empty one if there is no file yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("Invalid answer cache {}: {e}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Could not read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).unwrap();
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, json))
+            .map_err(|e| format!("Could not write {}: {e}", path.display()))
+    }
+
+    /// The remembered outcome for `key`, if any.
+    pub fn get(&self, key: &Key) -> Option<Outcome> {
+        let entry = self.entries.iter().find(|entry| {
+            (entry.day, entry.part, entry.input.as_str(), entry.version.as_str())
+                == (key.day, key.part, key.input.as_str(), key.version)
+        })?;
+        Some(Outcome {
+            day: entry.day,
+            part: entry.part,
+            solver: None,
+            answer: Some(entry.answer.clone()),
+            elapsed: Duration::from_nanos(entry.elapsed_ns),
+            status: Status::Ok,
+            cached: true,
+        })
+    }
+
+    /// Remembers the answer in `outcome` under `key`, replacing what was
+    /// remembered for the same input with older versions of the solution.
+    /// Outcomes without an answer are not remembered.
+    pub fn insert(&mut self, key: Key, outcome: &Outcome) {
+        let (Status::Ok, Some(answer)) = (&outcome.status, &outcome.answer) else { return };
+        self.entries.retain(|entry| (entry.day, entry.part, &entry.input) != (key.day, key.part, &key.input));
+        self.entries.push(Entry {
+            day: key.day,
+            part: key.part,
+            input: key.input,
+            version: key.version.to_string(),
+            answer: answer.clone(),
+            elapsed_ns: outcome.elapsed.as_nanos() as u64,
+            recorded: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs()),
+        });
+        self.entries.sort_by_key(|entry| (entry.day, entry.part));
+    }
+
+    /// Forgets the answers for `days`, or every answer if `days` is empty.
+    /// Returns how many were forgotten.
+    pub fn clear(&mut self, days: &[u8]) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|entry| !days.is_empty() && !days.contains(&entry.day));
+        before - self.entries.len()
+    }
+
+    /// Answers what it can of `runs` from the cache, and passes the rest to
+    /// `solve`, remembering the answers it finds. Cached outcomes come first.
+    pub fn solve_with<T>(
+        &mut self,
+        runs: Vec<(T, Solution, PathBuf)>,
+        solve: impl FnOnce(Vec<((T, Option<Key>), Solution, PathBuf)>) -> Vec<((T, Option<Key>), Outcome)>,
+    ) -> Vec<(T, Outcome)> {
+        let mut outcomes = vec![];
+        let mut uncached = vec![];
+        for (tag, solution, input) in runs {
+            let key = Key::new(&solution, &input);
+            match key.as_ref().and_then(|key| self.get(key)) {
+                Some(outcome) => {
+                    tracing::debug!(day = solution.day, part = solution.part, "answered from the cache");
+                    outcomes.push((tag, outcome));
+                }
+                None => uncached.push(((tag, key), solution, input)),
+            }
+        }
+
+        for ((tag, key), outcome) in solve(uncached) {
+            if let Some(key) = key {
+                self.insert(key, &outcome);
+            }
+            outcomes.push((tag, outcome));
+        }
+        outcomes
+    }
+}
+
+/// Renders the entries as a table, one row per answer.
+pub fn table(entries: &[Entry]) -> String {
+    let rows = entries.iter()
+        .map(|entry| [
+            entry.day.to_string(),
+            entry.part.to_string(),
+            report::answer_cell(Some(&entry.answer)),
+            format!("{:.2?}", Duration::from_nanos(entry.elapsed_ns)),
+            entry.input[..12].to_string(),
+            entry.version.clone(),
+        ])
+        .collect::<Vec<_>>();
+    report::render(["Day", "Part", "Answer", "Time", "Input", "Version"], &rows, 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::execute::{self, Timeouts};
+
+    fn input(test: &str, text: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("runner_cache").join(test);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.txt");
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn solve_all(cache: &mut Cache, solutions: &[Solution], input: &Path, runs: &Cell<usize>) -> Vec<Outcome> {
+        let runs_wanted = solutions.iter()
+            .map(|solution| ((), solution.clone(), input.to_path_buf()))
+            .collect();
+        cache.solve_with(runs_wanted, |uncached| {
+            runs.set(runs.get() + uncached.len());
+            execute::solve_each(uncached, &Timeouts::default())
+        })
+        .into_iter()
+        .map(|((), outcome)| outcome)
+        .collect()
+    }
+
+    #[test]
+    fn answers_unchanged_runs() {
+        let solutions = [
+            Solution::new(1, 1, |path| Answer::Integer(fs::read_to_string(path).unwrap().len() as i64)),
+            Solution::new(1, 2, |_| panic!("not yet")),
+        ];
+        let first = input("answers_unchanged_runs_1", "12345");
+        let second = input("answers_unchanged_runs_2", "123");
+        let runs = Cell::new(0);
+        let mut cache = Cache::default();
+
+        let outcomes = solve_all(&mut cache, &solutions, &first, &runs);
+        assert_eq!(runs.get(), 2);
+        assert!(!outcomes[0].cached);
+        // Only answers are remembered, so the panic runs again
+        assert_eq!(cache.entries.len(), 1);
+
+        let outcomes = solve_all(&mut cache, &solutions, &first, &runs);
+        assert_eq!(runs.get(), 3);
+        assert!(outcomes[0].cached);
+        assert_eq!(outcomes[0].answer, Some(Answer::Integer(5)));
+        assert!(matches!(outcomes[1].status, Status::Panicked(_)));
+
+        // Another input is another answer
+        let outcomes = solve_all(&mut cache, &solutions, &second, &runs);
+        assert_eq!(runs.get(), 5);
+        assert_eq!(outcomes[0].answer, Some(Answer::Integer(3)));
+        assert_eq!(cache.entries.len(), 2);
+    }
+
+    #[test]
+    fn new_versions_replace_old() {
+        let solution = Solution::new(1, 1, |_| Answer::Integer(1));
+        let path = input("new_versions_replace_old", "x");
+        let mut key = Key::new(&solution, &path).unwrap();
+        let outcome = execute::solve(solution.clone(), &path, None);
+        let mut cache = Cache::default();
+        cache.insert(key.clone(), &outcome);
+        assert!(cache.get(&key).is_some());
+
+        key.version = "something newer";
+        assert!(cache.get(&key).is_none());
+        cache.insert(key.clone(), &outcome);
+        assert_eq!(cache.entries.len(), 1);
+        assert_eq!(cache.entries[0].version, "something newer");
+    }
+
+    #[test]
+    fn clear_and_round_trip() {
+        let entry = |day| Entry {
+            day,
+            part: 1,
+            input: "ab".repeat(32),
+            version: "v1".into(),
+            answer: Answer::Grid(vec!["#.".into()]),
+            elapsed_ns: 1_500_000,
+            recorded: 0,
+        };
+        let mut cache = Cache { entries: vec![entry(1), entry(2), entry(3)] };
+
+        let path = std::env::temp_dir().join("runner_cache").join("round_trip.json");
+        cache.save(&path).unwrap();
+        assert_eq!(Cache::load(&path).unwrap().entries, cache.entries);
+        assert!(Cache::load(&path.with_extension("missing")).unwrap().entries.is_empty());
+
+        assert_eq!(cache.clear(&[2]), 1);
+        assert_eq!(cache.entries.iter().map(|entry| entry.day).collect::<Vec<_>>(), [1, 3]);
+        assert!(table(&cache.entries).contains("  3     1  #.      1.50ms  abababababab  v1"));
+        assert_eq!(cache.clear(&[]), 2);
+        assert!(cache.entries.is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! Checks inputs against their day's schema (see `aoc::schema`) without
+//! solving them, so a truncated or badly pasted input is caught with every
+//! line that is wrong, rather than as a panic deep inside a parser.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use aoc::schema::Violation;
+
+use crate::registry;
+
+/// How one input measured up against its day's schema.
+#[derive(Debug)]
+pub struct Checked {
+    pub day: u8,
+    pub input: PathBuf,
+    /// What is wrong with the input, or why it could not be checked
+    pub result: Result<Vec<Violation>, String>,
+}
+
+impl Checked {
+    pub fn is_ok(&self) -> bool {
+        self.result.as_ref().is_ok_and(Vec::is_empty)
+    }
+}
+
+/// Checks the input at `input` against `day`'s schema.
+pub fn check(day: u8, input: &Path) -> Checked {
+    let result = registry::schema(day)
+        .ok_or_else(|| format!("There is no solution for day {day}"))
+        .and_then(|schema| {
+            fs::read_to_string(input)
+                .map(|text| schema.check(&text))
+                .map_err(|e| format!("Could not read {}: {e}", input.display()))
+        });
+    Checked { day, input: input.to_path_buf(), result }
+}
+
+/// Says which inputs are fine, and lists everything wrong with the rest.
+pub fn report(checked: &[Checked]) -> String {
+    let mut report = String::new();
+    for checked in checked {
+        let input = checked.input.display();
+        match &checked.result {
+            Ok(violations) if violations.is_empty() => {
+                report += &format!("Day {}: ok ({input})\n", checked.day);
+            }
+            Ok(violations) => {
+                let problems = if violations.len() == 1 { "problem" } else { "problems" };
+                report += &format!("Day {}: {} {problems} in {input}\n", checked.day, violations.len());
+                for violation in violations {
+                    report += &format!("    {violation}\n");
+                }
+            }
+            Err(e) => report += &format!("Day {}: {e}\n", checked.day),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixtures_follow_their_schemas() {
+        for day in 1..=11 {
+            for fixture in fs::read_dir(registry::fixtures_dir(day)).unwrap() {
+                let checked = check(day, &fixture.unwrap().path());
+                assert!(checked.is_ok(), "{}", report(&[checked]));
+            }
+        }
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let dir = std::env::temp_dir().join("runner_check");
+        fs::create_dir_all(&dir).unwrap();
+
+        // The example with its last command's docks mistyped, a crate
+        // trimmed from a row, and a stray space
+        let path = dir.join("day_5.txt");
+        fs::write(&path, concat!(
+            "    [D]    \n",
+            "[N] [C]    \n",
+            "[Z] [M] [P\n",
+            " 1   2   3 \n",
+            "\n",
+            "move 1 from 2 to 1\n",
+            "move 3 from 1 to 3 \n",
+            "move 2 from 2 to 1\n",
+        ))
+        .unwrap();
+        let checked = check(5, &path);
+        assert!(!checked.is_ok());
+        let expected = format!("\
+Day 5: 2 problems in {}
+    line 3, column 9: expected a crate like \"[A]\" or a gap, found \"[P\"
+    line 7, column 18: invalid `to` \"3 \": invalid digit found in string
+", path.display());
+        assert_eq!(report(&[checked]), expected);
+
+        let path = dir.join("day_11.txt");
+        fs::write(&path, concat!(
+            "Monkey 0:\n",
+            "  Starting items: 79, 98\n",
+            "  Operation: new = old * 19\n",
+            "  Test: divisible by 23\n",
+            "    If true: throw to monkey 2\n",
+            "    If false: throw to monkey 0\n",
+        ))
+        .unwrap();
+        assert_eq!(
+            check(11, &path).result.unwrap().iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["line 5: there is no monkey 2", "line 6: monkey 0 throws to itself"],
+        );
+
+        assert!(report(&[check(5, &dir.join("missing.txt"))]).starts_with("Day 5: Could not read"));
+        assert_eq!(report(&[check(26, &path)]), "Day 26: There is no solution for day 26\n");
+    }
+}
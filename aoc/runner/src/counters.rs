@@ -0,0 +1,196 @@
+//! Hardware performance counters, for benchmarks that are not at the mercy
+//! of whatever else the machine is doing. Only Linux has them; elsewhere, or
+//! where the kernel or a container forbids them, opening fails with the
+//! reason and callers fall back to wall-clock time.
+
+/// Events counted on one thread between `Counters::start` and `stop`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Counts {
+    /// Instructions retired in user space
+    pub instructions: u64,
+    /// Last level cache misses. `None` where the CPU does not expose them,
+    /// as in many virtual machines.
+    pub cache_misses: Option<u64>,
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::Counters;
+
+#[cfg(not(target_os = "linux"))]
+pub use unsupported::Counters;
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{fs::File, io::{self, Read}, os::fd::{AsRawFd, FromRawFd}};
+
+    use super::Counts;
+
+    // From linux/perf_event.h
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_FORMAT_GROUP: u64 = 1 << 3;
+    const DISABLED: u64 = 1 << 0;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+    const PERF_EVENT_IOC_ENABLE: u64 = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: u64 = 0x2401;
+    const PERF_EVENT_IOC_RESET: u64 = 0x2403;
+    const PERF_IOC_FLAG_GROUP: u64 = 1;
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    /// The first version of `struct perf_event_attr`. The kernel accepts any
+    /// version it knows, and this one has every field needed here.
+    #[repr(C)]
+    #[derive(Default)]
+    struct Attr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    /// Counters for the calling thread. They only count that thread, so
+    /// `start` and `stop` must be called on the thread doing the work.
+    pub struct Counters {
+        leader: File,
+        /// Read through the leader, but closing it would stop the count
+        _cache_misses: Option<File>,
+    }
+
+    impl Counters {
+        /// Opens the counters, disabled, or says why they cannot be.
+        pub fn open() -> Result<Self, String> {
+            let leader = open(PERF_COUNT_HW_INSTRUCTIONS, None).map_err(|e| {
+                let paranoid = std::fs::read_to_string("/proc/sys/kernel/perf_event_paranoid")
+                    .map(|level| format!(" (perf_event_paranoid is {})", level.trim()))
+                    .unwrap_or_default();
+                format!("could not open the instruction counter: {e}{paranoid}")
+            })?;
+            let cache_misses = open(PERF_COUNT_HW_CACHE_MISSES, Some(&leader)).ok();
+            Ok(Self { leader, _cache_misses: cache_misses })
+        }
+
+        /// Zeroes and enables the counters.
+        pub fn start(&mut self) -> io::Result<()> {
+            self.ioctl(PERF_EVENT_IOC_RESET)?;
+            self.ioctl(PERF_EVENT_IOC_ENABLE)
+        }
+
+        /// Disables the counters and reads them.
+        pub fn stop(&mut self) -> io::Result<Counts> {
+            self.ioctl(PERF_EVENT_IOC_DISABLE)?;
+
+            // With PERF_FORMAT_GROUP a read gives the number of events, then
+            // each value in the order the events were opened
+            let mut buffer = [0_u8; 8 * 3];
+            let read = self.leader.read(&mut buffer)?;
+            let values = buffer[..read].chunks_exact(8)
+                .map(|chunk| u64::from_ne_bytes(chunk.try_into().unwrap()))
+                .collect::<Vec<_>>();
+            match values.as_slice() {
+                [1, instructions] => Ok(Counts { instructions: *instructions, cache_misses: None }),
+                [2, instructions, cache_misses] => Ok(Counts {
+                    instructions: *instructions,
+                    cache_misses: Some(*cache_misses),
+                }),
+                _ => Err(io::Error::new(io::ErrorKind::InvalidData, "unexpected counter values")),
+            }
+        }
+
+        fn ioctl(&self, request: u64) -> io::Result<()> {
+            // SAFETY: a perf event ioctl on a perf event file descriptor we own
+            let result = unsafe {
+                libc::ioctl(self.leader.as_raw_fd(), request as _, PERF_IOC_FLAG_GROUP)
+            };
+            if result == -1 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Opens a user space counter for the calling thread on any CPU, as the
+    /// leader of a new group or a member of `group`.
+    fn open(config: u64, group: Option<&File>) -> io::Result<File> {
+        let attr = Attr {
+            kind: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<Attr>() as u32,
+            config,
+            read_format: PERF_FORMAT_GROUP,
+            // Members follow their leader, so only the leader starts disabled
+            flags: if group.is_none() { DISABLED } else { 0 } | EXCLUDE_KERNEL | EXCLUDE_HV,
+            ..Attr::default()
+        };
+        let group = group.map_or(-1, |leader| leader.as_raw_fd());
+
+        // SAFETY: `attr` is a valid perf_event_attr that outlives the call
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const Attr,
+                0 as libc::pid_t,
+                -1 as libc::c_int,
+                group as libc::c_int,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the kernel just gave us this descriptor
+        Ok(unsafe { File::from_raw_fd(fd as i32) })
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod unsupported {
+    use std::io;
+
+    use super::Counts;
+
+    pub struct Counters;
+
+    impl Counters {
+        pub fn open() -> Result<Self, String> {
+            Err("performance counters are only supported on Linux".to_string())
+        }
+
+        pub fn start(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        pub fn stop(&mut self) -> io::Result<Counts> {
+            Ok(Counts::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_more_for_more_work() {
+        // Counters are often unavailable in containers and on CI machines
+        let Ok(mut counters) = Counters::open() else { return };
+
+        let mut count = |n: u64| {
+            counters.start().unwrap();
+            let sum = (0..n).map(std::hint::black_box).sum::<u64>();
+            std::hint::black_box(sum);
+            counters.stop().unwrap()
+        };
+        let little = count(1_000);
+        let lots = count(1_000_000);
+        assert!(little.instructions > 0);
+        assert!(lots.instructions > little.instructions * 100);
+    }
+}
@@ -0,0 +1,535 @@
+use std::{
+    any::Any,
+    cell::Cell,
+    collections::HashMap,
+    panic,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        mpsc, Arc, Once,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    counters::{Counters, Counts},
+    pool::ThreadPool,
+    external::{self, External},
+    registry::{self, Solution, Solver},
+    report::{Outcome, Status},
+};
+
+/// Wall-clock time limits for solutions, with optional per-day overrides.
+#[derive(Debug, Clone, Default)]
+pub struct Timeouts {
+    /// Limit for days without an override. `None` waits forever.
+    pub default: Option<Duration>,
+    pub per_day: HashMap<u8, Duration>,
+}
+
+impl Timeouts {
+    pub fn new(default: Option<Duration>) -> Self {
+        Self { default, per_day: HashMap::new() }
+    }
+
+    /// Overrides the limit for `day`.
+    pub fn with_day(mut self, day: u8, timeout: Duration) -> Self {
+        self.per_day.insert(day, timeout);
+        self
+    }
+
+    pub fn get(&self, day: u8) -> Option<Duration> {
+        self.per_day.get(&day).copied().or(self.default)
+    }
+}
+
+/// Parses a duration such as `30s`, `500ms`, `2m` or a bare number of seconds.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number = number.parse::<f64>()
+        .map_err(|_| format!("invalid duration {text:?}, expected e.g. `30s`, `500ms` or `2m`"))?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        unit => return Err(format!("unknown unit {unit:?} in {text:?}, expected `ms`, `s` or `m`")),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("duration {text:?} is out of range"))
+}
+
+/// Parses a per-day time limit such as `11=2m`.
+pub fn parse_day_timeout(text: &str) -> Result<(u8, Duration), String> {
+    let (day, duration) = text.split_once('=')
+        .ok_or_else(|| format!("expected `DAY=DURATION`, found {text:?}"))?;
+    let day = day.trim().parse::<u8>().map_err(|_| format!("invalid day {day:?}"))?;
+    Ok((day, parse_duration(duration)?))
+}
+
+/// Runs a single solution against the input at `input`. A panic inside the
+/// solution is caught and reported in the outcome rather than unwinding
+/// further.
+///
+/// A built-in solution runs on a thread of its own. If it is still running
+/// after `timeout`, that thread is abandoned (it cannot be stopped, but
+/// nothing waits on it) and the outcome reports the time out. An external
+/// solver's process is killed instead.
+pub fn solve(solution: Solution, input: &Path, timeout: Option<Duration>) -> Outcome {
+    solve_counted(solution, input, timeout, false).0
+}
+
+/// Like `solve`, but counts the built-in solutions it abandons in `abandoned`
+/// until their threads finish after all.
+pub fn solve_tracked(solution: Solution, input: &Path, timeout: Option<Duration>, abandoned: &Abandoned) -> Outcome {
+    solve_with(solution, input, timeout, false, abandoned).0
+}
+
+/// Like `solve`, but if `count` is set also counts the instructions and cache
+/// misses of the solution's thread. The counts are `None` if the solution did
+/// not finish or is external, or an error if the counters could not be used.
+pub fn solve_counted(
+    solution: Solution,
+    input: &Path,
+    timeout: Option<Duration>,
+    count: bool,
+) -> (Outcome, Option<Result<Counts, String>>) {
+    solve_with(solution, input, timeout, count, &Abandoned::default())
+}
+
+/// How many threads of timed-out solutions are still running. Clones share
+/// the count.
+#[derive(Debug, Clone, Default)]
+pub struct Abandoned(Arc<AtomicUsize>);
+
+impl Abandoned {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Where a solution's thread is: still running, finished, or abandoned by
+/// the caller while still running.
+const RUNNING: u8 = 0;
+const FINISHED: u8 = 1;
+const ABANDONED: u8 = 2;
+
+fn solve_with(
+    solution: Solution,
+    input: &Path,
+    timeout: Option<Duration>,
+    count: bool,
+    abandoned: &Abandoned,
+) -> (Outcome, Option<Result<Counts, String>>) {
+    let mut outcome = Outcome {
+        day: solution.day,
+        part: solution.part,
+        solver: None,
+        answer: None,
+        elapsed: Duration::ZERO,
+        status: Status::Ok,
+        cached: false,
+    };
+
+    if !input.is_file() {
+        tracing::warn!(day = solution.day, input = %input.display(), "missing input");
+        outcome.status = Status::MissingInput;
+        return (outcome, None);
+    }
+
+    let solve = match &solution.solver {
+        Solver::BuiltIn(solve) => *solve,
+        Solver::External(external) => {
+            return (solve_external(&solution, external, input, timeout, outcome), None);
+        }
+    };
+
+    let span = tracing::info_span!("solve", day = solution.day, part = solution.part);
+    let _entered = span.enter();
+
+    // Events from the solution should go where they would have gone had it
+    // run on this thread.
+    let dispatch = tracing::dispatcher::get_default(|dispatch| dispatch.clone());
+    let solution_span = span.clone();
+
+    let path = input.to_string_lossy().to_string();
+    let quiet = is_quiet();
+    let state = Arc::new(AtomicU8::new(RUNNING));
+    let (thread_state, thread_abandoned) = (Arc::clone(&state), abandoned.clone());
+    let (tx, rx) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name(format!("day {} part {}", solution.day, solution.part))
+        .spawn(move || {
+            QUIET.set(quiet);
+            tracing::dispatcher::with_default(&dispatch, || {
+                solution_span.in_scope(|| {
+                    // Counters only count the thread that opens them
+                    let mut counters = count.then(|| Counters::open().and_then(|mut counters| {
+                        counters.start().map_err(|e| e.to_string())?;
+                        Ok(counters)
+                    }));
+
+                    let start = Instant::now();
+                    let result = panic::catch_unwind(|| solve(&path));
+                    let elapsed = start.elapsed();
+
+                    let counts = counters.as_mut().map(|counters| match counters {
+                        Ok(counters) => counters.stop().map_err(|e| e.to_string()),
+                        Err(e) => Err(e.clone()),
+                    });
+                    // The receiver is gone if we timed out; nobody is listening.
+                    let _ = tx.send((result, elapsed, counts));
+                })
+            });
+            if thread_state.swap(FINISHED, Ordering::SeqCst) == ABANDONED {
+                thread_abandoned.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        });
+    if let Err(e) = spawned {
+        outcome.status = Status::Panicked(format!("could not spawn thread: {e}"));
+        return (outcome, None);
+    }
+
+    let received = match timeout {
+        Some(timeout) => rx.recv_timeout(timeout).map_err(|e| match e {
+            mpsc::RecvTimeoutError::Timeout => Some(timeout),
+            mpsc::RecvTimeoutError::Disconnected => None,
+        }),
+        None => rx.recv().map_err(|_| None),
+    };
+
+    let mut counts = None;
+    match received {
+        Ok((Ok(answer), elapsed, counted)) => {
+            outcome.elapsed = elapsed;
+            tracing::info!(?elapsed, %answer, "solved");
+            outcome.answer = Some(answer);
+            counts = counted;
+        }
+        Ok((Err(payload), elapsed, _)) => {
+            outcome.elapsed = elapsed;
+            let message = panic_message(payload);
+            tracing::warn!(?elapsed, reason = %message, "panicked");
+            outcome.status = Status::Panicked(message);
+        }
+        Err(Some(timeout)) => {
+            // Counted first, so the thread can't uncount itself before that
+            abandoned.0.fetch_add(1, Ordering::SeqCst);
+            if state.compare_exchange(RUNNING, ABANDONED, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+                // It finished just now after all
+                abandoned.0.fetch_sub(1, Ordering::SeqCst);
+            }
+            outcome.elapsed = timeout;
+            tracing::warn!(?timeout, "timed out; abandoning its thread");
+            outcome.status = Status::TimedOut(timeout);
+        }
+        Err(None) => {
+            outcome.status = Status::Panicked("solution thread exited without an answer".to_string());
+        }
+    }
+
+    (outcome, counts)
+}
+
+/// Runs an external solver's program, filling in `outcome`.
+fn solve_external(
+    solution: &Solution,
+    external: &External,
+    input: &Path,
+    timeout: Option<Duration>,
+    mut outcome: Outcome,
+) -> Outcome {
+    let span = tracing::info_span!("solve", day = solution.day, part = solution.part, solver = %external.name);
+    let _entered = span.enter();
+    outcome.solver = Some(external.name.clone());
+
+    let start = Instant::now();
+    let result = external.run(solution.day, solution.part, input, timeout);
+    outcome.elapsed = start.elapsed();
+    match result {
+        Ok(answer) => {
+            tracing::info!(elapsed = ?outcome.elapsed, %answer, "solved");
+            outcome.answer = Some(answer);
+        }
+        Err(external::Error::TimedOut(timeout)) => {
+            tracing::warn!(?timeout, "timed out; killed it");
+            outcome.elapsed = timeout;
+            outcome.status = Status::TimedOut(timeout);
+        }
+        Err(external::Error::LimitExceeded(limit)) => {
+            tracing::warn!(elapsed = ?outcome.elapsed, limit, "exceeded a resource limit");
+            outcome.status = Status::LimitExceeded(limit.to_string());
+        }
+        Err(external::Error::Failed(message)) => {
+            tracing::warn!(elapsed = ?outcome.elapsed, reason = %message, "failed");
+            outcome.status = Status::Failed(message);
+        }
+    }
+    outcome
+}
+
+/// Runs every solution concurrently on a pool of `jobs` worker threads.
+/// Outcomes are returned in the order the solutions finished.
+pub fn solve_all(solutions: &[Solution], inputs_dir: &Path, jobs: usize, timeouts: &Timeouts) -> Vec<Outcome> {
+    let runs = solutions.iter()
+        .map(|solution| ((), solution.clone(), registry::input_path(inputs_dir, solution.day)))
+        .collect();
+
+    solve_many(runs, jobs, timeouts).into_iter()
+        .map(|((), outcome)| outcome)
+        .collect()
+}
+
+/// Runs each solution on its input in turn. Each outcome is returned with the
+/// tag it was given, in the order the runs were given.
+pub fn solve_each<T>(runs: Vec<(T, Solution, PathBuf)>, timeouts: &Timeouts) -> Vec<(T, Outcome)> {
+    runs.into_iter()
+        .map(|(tag, solution, input)| {
+            let timeout = timeouts.get(solution.day);
+            (tag, solve(solution, &input, timeout))
+        })
+        .collect()
+}
+
+/// Runs each solution on its input concurrently, on a pool of `jobs` worker
+/// threads. Each outcome is returned with the tag it was given, in the order
+/// the runs finished.
+pub fn solve_many<T>(runs: Vec<(T, Solution, PathBuf)>, jobs: usize, timeouts: &Timeouts) -> Vec<(T, Outcome)>
+where
+    T: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<(T, Outcome)>();
+    let pool = ThreadPool::new(jobs);
+    for (tag, solution, input) in runs {
+        let tx = tx.clone();
+        let timeout = timeouts.get(solution.day);
+        pool.execute(move || {
+            // Panics are reported in the summary table, so the default hook
+            // printing each one to stderr would only garble the output.
+            tx.send((tag, quietly(|| solve(solution, &input, timeout)))).unwrap();
+        });
+    }
+
+    // Required for loop to finish
+    drop(tx);
+    rx.iter().collect()
+}
+
+thread_local! {
+    /// Whether panics on this thread are kept out of stderr.
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with panics on this thread, and in the solutions it runs, kept
+/// out of stderr. They are still caught and reported as usual.
+///
+/// Rather than swapping the process's panic hook in and out, which races with
+/// anything else doing the same, one hook is installed for good that checks
+/// a flag for the thread that panicked.
+pub fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    /// Puts the flag back however `f` ends.
+    struct Reset(bool);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            QUIET.set(self.0);
+        }
+    }
+
+    install_panic_hook();
+    let _reset = Reset(QUIET.replace(true));
+    f()
+}
+
+/// Whether this thread is inside `quietly`.
+pub fn is_quiet() -> bool {
+    QUIET.get()
+}
+
+/// Wraps the current panic hook, once, in one that does nothing for threads
+/// inside `quietly`.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !is_quiet() {
+                hook(info);
+            }
+        }));
+    });
+}
+
+/// Pulls the message out of a panic payload. Panics raised with `panic!`,
+/// `unwrap` or `expect` carry either a `&str` or a `String`.
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Cargo.toml";
+
+    #[test]
+    fn isolates_panics() {
+        let solutions = [
+            Solution::new(1, 1, |_| "fine".into()),
+            Solution::new(1, 2, |_| panic!("bad input")),
+            Solution::new(2, 1, |path| path.len().into()),
+        ];
+
+        let mut outcomes = solutions.iter()
+            .map(|solution| solve(solution.clone(), Path::new(INPUT), None))
+            .collect::<Vec<_>>();
+        outcomes.sort_by_key(|outcome| (outcome.day, outcome.part));
+
+        assert_eq!(outcomes[0].answer, Some("fine".into()));
+        assert_eq!(outcomes[1].status, Status::Panicked("bad input".to_string()));
+        assert_eq!(outcomes[1].answer, None);
+        assert_eq!(outcomes[2].answer, Some(10_u32.into()));
+    }
+
+    #[test]
+    fn logs_each_run() {
+        let solution = Solution::new(3, 2, |_| panic!("bad input"));
+        let (_, logs) = aoc::logging::capture(|| solve(solution, Path::new(INPUT), None));
+        assert!(logs.contains("solve{day=3 part=2}"));
+        assert!(logs.contains("panicked"));
+        assert!(logs.contains("reason=bad input"));
+    }
+
+    #[test]
+    fn missing_input() {
+        let solution = Solution::new(1, 1, |_| unreachable!());
+        let outcome = solve(solution, Path::new("does/not/exist.txt"), None);
+        assert_eq!(outcome.status, Status::MissingInput);
+    }
+
+    #[test]
+    fn solve_all_runs_everything() {
+        let inputs_dir = std::env::temp_dir().join("runner_solve_all");
+        std::fs::create_dir_all(&inputs_dir).unwrap();
+        for day in 1..=6 {
+            std::fs::write(registry::input_path(&inputs_dir, day), "input").unwrap();
+        }
+
+        let mut solutions = (1..=6)
+            .map(|day| Solution::new(day, 1, |_| "x".into()))
+            .collect::<Vec<_>>();
+        solutions[3].solver = Solver::BuiltIn(|_| panic!("day 4 is broken"));
+
+        let mut outcomes = solve_all(&solutions, &inputs_dir, 3, &Timeouts::default());
+        outcomes.sort_by_key(|outcome| outcome.day);
+
+        assert_eq!(outcomes.len(), 6);
+        assert_eq!(outcomes[3].status, Status::Panicked("day 4 is broken".to_string()));
+        assert_eq!(outcomes.iter().filter(|outcome| outcome.is_ok()).count(), 5);
+    }
+
+    #[test]
+    fn times_out() {
+        let solutions = [
+            Solution::new(7, 1, |_| loop { thread::sleep(Duration::from_millis(10)) }),
+            Solution::new(8, 1, |_| {
+                thread::sleep(Duration::from_millis(50));
+                "slow but fine".into()
+            }),
+        ];
+        let timeouts = Timeouts::new(Some(Duration::from_millis(20)))
+            .with_day(8, Duration::from_secs(10));
+
+        let start = Instant::now();
+        let outcome = solve(solutions[0].clone(), Path::new(INPUT), timeouts.get(7));
+        assert_eq!(outcome.status, Status::TimedOut(Duration::from_millis(20)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let outcome = solve(solutions[1].clone(), Path::new(INPUT), timeouts.get(8));
+        assert_eq!(outcome.answer, Some("slow but fine".into()));
+    }
+
+    #[test]
+    fn tracks_abandoned_threads() {
+        let abandoned = Abandoned::default();
+        let solution = Solution::new(7, 2, |_| {
+            thread::sleep(Duration::from_millis(200));
+            "late".into()
+        });
+        let outcome = solve_tracked(solution.clone(), Path::new(INPUT), Some(Duration::from_millis(10)), &abandoned);
+        assert_eq!(outcome.status, Status::TimedOut(Duration::from_millis(10)));
+        assert_eq!(abandoned.count(), 1);
+
+        // Finishing late uncounts it
+        let start = Instant::now();
+        while abandoned.count() > 0 {
+            assert!(start.elapsed() < Duration::from_secs(5), "never finished");
+            thread::sleep(Duration::from_millis(10));
+        }
+        solve_tracked(solution, Path::new(INPUT), None, &abandoned);
+        assert_eq!(abandoned.count(), 0);
+    }
+
+    #[test]
+    fn solution_logs_stay_in_span() {
+        let solution = Solution::new(9, 1, |_| {
+            tracing::debug!("inside the solution");
+            1_u32.into()
+        });
+        let (_, logs) = aoc::logging::capture(|| solve(solution, Path::new(INPUT), None));
+        assert!(logs.contains("solve{day=9 part=1}"));
+        assert!(logs.contains("inside the solution"));
+    }
+
+    #[test]
+    fn quiet_threads() {
+        assert!(!is_quiet());
+        let quiet = quietly(|| {
+            let solution = Solution::new(4, 2, |_| is_quiet().to_string().into());
+            solve(solution, Path::new(INPUT), None).answer
+        });
+        // The solution's own thread was quiet too, and the flag is reset
+        assert_eq!(quiet, Some("true".into()));
+        assert!(!is_quiet());
+
+        let panicked = panic::catch_unwind(|| quietly(|| panic!("quietly")));
+        assert!(panicked.is_err());
+        assert!(!is_quiet());
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("3h").is_err());
+        assert!(parse_duration("99999999999999999999s").is_err());
+
+        assert_eq!(parse_day_timeout("11=2m"), Ok((11, Duration::from_secs(120))));
+        assert!(parse_day_timeout("11").is_err());
+    }
+
+    #[test]
+    fn counted() {
+        let solution = Solution::new(2, 1, |_| (0..10_000_u64).sum::<u64>().into());
+        let (outcome, counts) = solve_counted(solution.clone(), Path::new(INPUT), None, true);
+        assert!(outcome.is_ok());
+        // Either the counters worked, or we were told why not
+        match counts {
+            Some(Ok(counts)) => assert!(counts.instructions > 0),
+            Some(Err(reason)) => assert!(!reason.is_empty()),
+            None => panic!("counts were requested"),
+        }
+
+        let (_, counts) = solve_counted(solution, Path::new(INPUT), None, false);
+        assert_eq!(counts, None);
+    }
+}
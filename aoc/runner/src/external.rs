@@ -0,0 +1,386 @@
+//! Solutions written in other languages, run as separate programs.
+//!
+//! Each line of the solvers file registers a program for some parts of a day:
+//!
+//! ```text
+//! # <name> <day> <parts> <program> [arguments...]
+//! alice 5 1,2 python3 alice/day_5.py
+//! ```
+//!
+//! Paths in the command are relative to the solvers file. The program gets
+//! the puzzle input on stdin, and the day and part wanted in the `AOC_DAY` and
+//! `AOC_PART` environment variables. It prints its answers on stdout, one per
+//! line in the same form as the batch sidecars, e.g. `1: 24000`. Pictures are
+//! printed a row at a time as `<part>| <row>`. Any other output on stdout is
+//! an error; use stderr for logging.
+//!
+//! Programs run under the resource limits in `sandbox`, each in a process
+//! group of its own, so that anything they start is killed with them when
+//! they time out.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use aoc::Answer;
+
+use crate::{
+    registry::{Solution, Solver},
+    sandbox::Limits,
+};
+
+/// How often a running program is checked for having finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// A registered program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct External {
+    /// Who or what the solver is, e.g. its author
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// Folder the program runs in: the one holding the solvers file
+    pub dir: PathBuf,
+    pub limits: Limits,
+}
+
+/// Why a program gave no answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Still running after the time limit, so it was killed
+    TimedOut(Duration),
+    /// Ran into one of its resource limits. Holds which.
+    LimitExceeded(&'static str),
+    Failed(String),
+}
+
+impl External {
+    /// Runs the program on `input` and returns its answer for `part`,
+    /// killing it if it runs for longer than `timeout`.
+    pub fn run(&self, day: u8, part: u8, input: &Path, timeout: Option<Duration>) -> Result<Answer, Error> {
+        let input = fs::read(input)
+            .map_err(|e| Error::Failed(format!("could not read {}: {e}", input.display())))?;
+
+        let mut command = Command::new(&self.program);
+        self.limits.apply(&mut command);
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command
+            .args(&self.args)
+            .current_dir(&self.dir)
+            .env("AOC_DAY", day.to_string())
+            .env("AOC_PART", part.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Failed(format!("could not start {}: {e}", self.program)))?;
+
+        // Feed and drain the pipes on their own threads, so a program that
+        // writes before it has read everything cannot deadlock with us
+        let mut stdin = child.stdin.take().unwrap();
+        thread::spawn(move || {
+            // A program that only needs part of the input may close early
+            let _ = stdin.write_all(&input);
+        });
+        let stdout = drain(child.stdout.take().unwrap());
+        let stderr = drain(child.stderr.take().unwrap());
+
+        let start = Instant::now();
+        let timed_out = |mut child: Child, timeout| {
+            kill_group(child.id());
+            let _ = child.kill();
+            let _ = child.wait();
+            Err(Error::TimedOut(timeout))
+        };
+        let (status, cpu_used) = loop {
+            match wait(&mut child) {
+                Ok(Some(exited)) => break exited,
+                Ok(None) => {}
+                Err(e) => return Err(Error::Failed(format!("could not wait for {}: {e}", self.program))),
+            }
+            if let Some(timeout) = timeout.filter(|&timeout| start.elapsed() >= timeout) {
+                return timed_out(child, timeout);
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        // Anything the program started may still hold the pipes open, so
+        // only wait for them until the time is up
+        let finish = |pipe: &mpsc::Receiver<String>| match timeout {
+            Some(timeout) => pipe.recv_timeout(timeout.saturating_sub(start.elapsed())).map_err(|_| timeout),
+            None => Ok(pipe.recv().unwrap_or_default()),
+        };
+        let (stdout, stderr) = match (finish(&stdout), finish(&stderr)) {
+            (Ok(stdout), Ok(stderr)) => (stdout, stderr),
+            (Err(timeout), _) | (_, Err(timeout)) => {
+                // The program itself has exited, but its group lives on
+                // while anything it started does
+                kill_group(child.id());
+                return Err(Error::TimedOut(timeout));
+            }
+        };
+        if !status.success() {
+            if let Some(limit) = self.limits.exceeded(status, cpu_used) {
+                return Err(Error::LimitExceeded(limit));
+            }
+            let last = stderr.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("");
+            return Err(Error::Failed(format!("{status}: {last}").trim_end_matches([' ', ':']).to_string()));
+        }
+
+        let mut answers = parse_answers(&stdout).map_err(Error::Failed)?;
+        answers.remove(&part).ok_or_else(|| Error::Failed(format!("no answer for part {part}")))
+    }
+}
+
+/// Reads all of `pipe` on a thread of its own, sending what it read once the
+/// pipe is closed.
+fn drain(mut pipe: impl Read + Send + 'static) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut bytes = vec![];
+        let _ = pipe.read_to_end(&mut bytes);
+        let _ = tx.send(String::from_utf8_lossy(&bytes).into_owned());
+    });
+    rx
+}
+
+/// The program's exit status and the CPU time it used, if it has exited.
+/// The CPU time is only known on Linux.
+#[cfg(target_os = "linux")]
+fn wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    // SAFETY: rusage is plain data, which wait4 fills in
+    let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+    // SAFETY: wait4 only writes to the two pointers, which are valid
+    match unsafe { libc::wait4(child.id() as libc::pid_t, &mut status, libc::WNOHANG, &mut usage) } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(None),
+        _ => {
+            let time = |time: libc::timeval| Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000);
+            Ok(Some((ExitStatus::from_raw(status), Some(time(usage.ru_utime) + time(usage.ru_stime)))))
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Option<Duration>)>> {
+    Ok(child.try_wait()?.map(|status| (status, None)))
+}
+
+/// Kills everything in the process group the program with id `id` started.
+/// Elsewhere than Linux, does nothing.
+fn kill_group(id: u32) {
+    #[cfg(target_os = "linux")]
+    // SAFETY: killpg only sends a signal. The program was started with
+    // `process_group(0)`, so the group is its own, and its id is not reused
+    // while the group has anyone left in it.
+    unsafe {
+        libc::killpg(id as libc::pid_t, libc::SIGKILL);
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = id;
+}
+
+/// Reads the answers a program printed, by part.
+pub fn parse_answers(stdout: &str) -> Result<BTreeMap<u8, Answer>, String> {
+    let mut answers = BTreeMap::new();
+    for (i, line) in stdout.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let split = line.find([':', '|'])
+            .and_then(|at| Some((line[..at].trim().parse::<u8>().ok()?, &line[at..at + 1], &line[at + 1..])));
+        match split {
+            Some((part, ":", answer)) => {
+                answers.insert(part, Answer::Text(answer.trim().to_string()));
+            }
+            Some((part, _, row)) => {
+                let row = row.strip_prefix(' ').unwrap_or(row).to_string();
+                match answers.entry(part).or_insert_with(|| Answer::Grid(vec![])) {
+                    Answer::Grid(rows) => rows.push(row),
+                    _ => return Err(format!("line {}: part {part} has both an answer and a picture", i + 1)),
+                }
+            }
+            None => return Err(format!("line {}: expected `<part>: <answer>`, found {line:?}", i + 1)),
+        }
+    }
+    Ok(answers)
+}
+
+/// Reads the solvers file at `path`, giving each solver `limits`. A missing
+/// file registers nothing.
+pub fn load(path: &Path, limits: Limits) -> Result<Vec<Solution>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => {
+            let dir = path.parent().unwrap_or(Path::new("."));
+            parse(&text, dir, limits).map_err(|e| format!("Invalid solvers file {}: {e}", path.display()))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(format!("Could not read {}: {e}", path.display())),
+    }
+}
+
+/// Parses a solvers file, giving a solution for each part registered.
+/// Commands run in `dir`, under `limits`.
+pub fn parse(text: &str, dir: &Path, limits: Limits) -> Result<Vec<Solution>, String> {
+    let mut solutions = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = |what: &str| format!("line {}: {what} in {line:?}", i + 1);
+        let mut words = line.split_whitespace();
+        let (Some(name), Some(day), Some(parts), Some(program)) =
+            (words.next(), words.next(), words.next(), words.next())
+        else {
+            return Err(error("expected `<name> <day> <parts> <program> [arguments...]`"));
+        };
+        let day = day.parse::<u8>().map_err(|_| error("invalid day"))?;
+        let parts = parts.split(',')
+            .map(|part| part.parse::<u8>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| error("invalid parts"))?;
+
+        let external = Arc::new(External {
+            name: name.to_string(),
+            program: program.to_string(),
+            args: words.map(str::to_string).collect(),
+            dir: dir.to_path_buf(),
+            limits,
+        });
+        for part in parts {
+            solutions.push(Solution { day, part, solver: Solver::External(Arc::clone(&external)) });
+        }
+    }
+    Ok(solutions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A solver that runs `script` with `sh`, from a scratch folder holding
+    /// the input `input.txt`.
+    fn script(test: &str, script: &str, limits: Limits) -> (External, PathBuf) {
+        let dir = std::env::temp_dir().join("runner_external").join(test);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("solve.sh"), script).unwrap();
+        fs::write(dir.join("input.txt"), "3\n4\n").unwrap();
+
+        let external = External {
+            name: test.to_string(),
+            program: "sh".to_string(),
+            args: vec!["solve.sh".to_string()],
+            dir: dir.clone(),
+            limits,
+        };
+        (external, dir.join("input.txt"))
+    }
+
+    #[test]
+    fn parse_registry() {
+        let text = "# name day parts command\nalice 5 1,2 python3 day_5.py --fast\n\nbob 11 2 ./day_11\n";
+        let solutions = parse(text, Path::new("/team"), Limits::NONE).unwrap();
+        let registered = solutions.iter()
+            .map(|solution| (solution.day, solution.part, solution.name()))
+            .collect::<Vec<_>>();
+        assert_eq!(registered, [(5, 1, "alice"), (5, 2, "alice"), (11, 2, "bob")]);
+
+        let Solver::External(alice) = &solutions[0].solver else { panic!("not external") };
+        assert_eq!(alice.program, "python3");
+        assert_eq!(alice.args, ["day_5.py", "--fast"]);
+        assert_eq!(alice.dir, Path::new("/team"));
+
+        assert!(parse("alice 5 1,2", Path::new("."), Limits::NONE).unwrap_err().contains("line 1"));
+        assert!(parse("alice 5 one ./solve", Path::new("."), Limits::NONE).unwrap_err().contains("invalid parts"));
+        assert!(load(Path::new("does/not/exist.txt"), Limits::NONE).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_protocol() {
+        let answers = parse_answers("1: 24000\n2| #..#\n2| .##.\n").unwrap();
+        assert_eq!(answers[&1], Answer::Text("24000".into()));
+        assert_eq!(answers[&2], Answer::Grid(vec!["#..#".into(), ".##.".into()]));
+
+        assert!(parse_answers("the answer is 7").unwrap_err().contains("line 1"));
+        assert!(parse_answers("1: 7\n1| #").is_err());
+    }
+
+    #[test]
+    fn run_program() {
+        // Sums the input for part 1, and says which day it was asked for
+        let (external, input) = script("run_program", "\
+            sum=0; while read n; do sum=$((sum + n)); done\n\
+            echo \"1: $sum\"\n\
+            echo \"2: day $AOC_DAY part $AOC_PART\"\n", Limits::default());
+        assert_eq!(external.run(5, 1, &input, None), Ok(Answer::Text("7".into())));
+        assert_eq!(external.run(5, 2, &input, None), Ok(Answer::Text("day 5 part 2".into())));
+        assert_eq!(external.run(5, 3, &input, None), Err(Error::Failed("no answer for part 3".into())));
+    }
+
+    #[test]
+    fn failures() {
+        let (external, input) = script("failures", "echo 'no idea' >&2; exit 3\n", Limits::default());
+        let Err(Error::Failed(message)) = external.run(1, 1, &input, None) else { panic!("should fail") };
+        assert!(message.contains("exit status: 3"), "{message}");
+        assert!(message.ends_with("no idea"), "{message}");
+
+        let (external, input) = script("timeout", "sleep 10\n", Limits::default());
+        let start = Instant::now();
+        let timeout = Duration::from_millis(50);
+        assert_eq!(external.run(1, 1, &input, Some(timeout)), Err(Error::TimedOut(timeout)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn kills_what_it_started() {
+        // The background sleep is the program's child, and would outlive it
+        let (external, input) = script("kills_group", "sleep 10 & echo $! > sleep.pid; wait\n", Limits::default());
+        let timeout = Duration::from_millis(200);
+        assert_eq!(external.run(1, 1, &input, Some(timeout)), Err(Error::TimedOut(timeout)));
+        let pid = fs::read_to_string(external.dir.join("sleep.pid")).unwrap();
+        // Gone once reaped, or a zombie until then
+        let running = || {
+            fs::read_to_string(format!("/proc/{}/stat", pid.trim()))
+                .is_ok_and(|stat| stat.rsplit(')').next().is_some_and(|rest| !rest.trim_start().starts_with('Z')))
+        };
+        let start = Instant::now();
+        while running() {
+            assert!(start.elapsed() < Duration::from_secs(5), "sleep {} is still running", pid.trim());
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        // Exits at once, but leaves the sleep holding its stdout open
+        let (external, input) = script("holds_pipes", "echo '1: 7'; sleep 10 &\n", Limits::default());
+        let start = Instant::now();
+        assert_eq!(external.run(1, 1, &input, Some(timeout)), Err(Error::TimedOut(timeout)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn limits() {
+        let limits = Limits { cpu: Some(Duration::from_secs(1)), ..Limits::NONE };
+        let (external, input) = script("cpu_limit", "while :; do :; done\n", limits);
+        assert_eq!(external.run(1, 1, &input, None), Err(Error::LimitExceeded("CPU time")));
+
+        let limits = Limits { open_files: Some(16), ..Limits::NONE };
+        let (external, input) = script("open_files_limit", "echo \"1: $(ulimit -n)\"\n", limits);
+        assert_eq!(external.run(1, 1, &input, None), Ok(Answer::Text("16".into())));
+    }
+}
@@ -0,0 +1,320 @@
+//! A log of every run's answers and timings, to see how each day changed as
+//! it was rewritten (e.g. day 8 going from one thread to many). Each line of
+//! the history file is one execution of the runner, as JSON, so recording a
+//! run only ever appends.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use aoc::Answer;
+use serde::{Deserialize, Serialize};
+
+use crate::report::{self, Outcome, Status};
+
+/// One execution of the runner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    /// Seconds since the Unix epoch
+    pub recorded: u64,
+    /// The commit the workspace was at, see `baseline::current_commit`
+    pub commit: Option<String>,
+    /// The command that ran, e.g. `all`
+    pub command: String,
+    pub records: Vec<Record>,
+}
+
+/// How one part went in a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solver: Option<String>,
+    pub answer: Option<Answer>,
+    pub elapsed_ns: u64,
+    /// `ok`, or what went wrong
+    pub status: String,
+    /// Answered from the cache, so the time is not this run's
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// SHA-256 of the input, see `cache::input_hash`. Runs are only compared
+    /// with others on the same input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+}
+
+impl Record {
+    pub fn new(outcome: &Outcome, input: Option<String>) -> Self {
+        Self {
+            day: outcome.day,
+            part: outcome.part,
+            solver: outcome.solver.clone(),
+            answer: outcome.answer.clone(),
+            elapsed_ns: outcome.elapsed.as_nanos() as u64,
+            status: outcome.status.to_string(),
+            cached: outcome.cached,
+            input,
+        }
+    }
+
+    fn is_ok(&self) -> bool {
+        self.status == Status::Ok.to_string()
+    }
+
+    fn series(&self) -> Series<'_> {
+        (self.part, self.solver.as_deref(), self.input.as_deref())
+    }
+}
+
+/// The records whose times and answers are compared with each other: those
+/// of one part, by one solver, on one input.
+type Series<'a> = (u8, Option<&'a str>, Option<&'a str>);
+
+impl Run {
+    pub fn new(command: &str, commit: Option<String>, records: Vec<Record>) -> Self {
+        let recorded = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Self { recorded, commit, command: command.to_string(), records }
+    }
+}
+
+/// Adds `run` to the end of the history at `path`.
+pub fn append(path: &Path, run: &Run) -> Result<(), String> {
+    let line = serde_json::to_string(run).unwrap();
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| OpenOptions::new().create(true).append(true).open(path))
+        .and_then(|mut file| writeln!(file, "{line}"))
+        .map_err(|e| format!("Could not write {}: {e}", path.display()))
+}
+
+/// Reads every run in the history at `path`, oldest first. There are none if
+/// there is no file yet.
+pub fn load(path: &Path) -> Result<Vec<Run>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(format!("Could not read {}: {e}", path.display())),
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("Invalid history in {} line {}: {e}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+/// How the time of each part of `day` (or just `part`) changed over the runs
+/// that worked it out on the same input. Answers from the cache and failed
+/// runs are left out.
+pub fn trend(runs: &[Run], day: u8, part: Option<u8>) -> String {
+    let mut previous: Vec<(Series, u64)> = vec![];
+    let mut rows = vec![];
+    for (run, record) in records(runs, day, part) {
+        if record.cached || !record.is_ok() {
+            continue;
+        }
+        let key = record.series();
+        let change = match previous.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, before)) => {
+                let change = (record.elapsed_ns as f64 / *before as f64 - 1.0) * 100.0;
+                *before = record.elapsed_ns;
+                format!("{change:+.1}%")
+            }
+            None => {
+                previous.push((key, record.elapsed_ns));
+                "-".to_string()
+            }
+        };
+        rows.push([
+            record.part.to_string(),
+            when(run.recorded),
+            commit_cell(run.commit.as_deref()),
+            run.command.clone(),
+            report::solver_cell(record.solver.as_deref()),
+            input_cell(record.input.as_deref()),
+            format!("{:.2?}", Duration::from_nanos(record.elapsed_ns)),
+            change,
+        ]);
+    }
+    report::render(["Part", "When", "Commit", "Command", "Solver", "Input", "Time", "Change"], &rows, 1)
+}
+
+/// The runs where an answer to `day` differed from the one before it on the
+/// same input, for each part and solver, starting with the first answer
+/// found.
+pub fn answer_changes(runs: &[Run], day: u8) -> String {
+    let mut previous: Vec<(Series, &Answer)> = vec![];
+    let mut rows = vec![];
+    for (run, record) in records(runs, day, None) {
+        let Some(answer) = record.answer.as_ref().filter(|_| record.is_ok()) else { continue };
+        let key = record.series();
+        let before = match previous.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, before)) if *before == answer => continue,
+            Some((_, before)) => Some(std::mem::replace(before, answer)),
+            None => {
+                previous.push((key, answer));
+                None
+            }
+        };
+        rows.push([
+            record.part.to_string(),
+            when(run.recorded),
+            commit_cell(run.commit.as_deref()),
+            report::solver_cell(record.solver.as_deref()),
+            input_cell(record.input.as_deref()),
+            answer.to_string(),
+            report::answer_cell(before),
+        ]);
+    }
+    report::render(["Part", "When", "Commit", "Solver", "Input", "Answer", "Was"], &rows, 1)
+}
+
+/// Each record of `day` (and `part`, if given) with the run it is from.
+fn records(runs: &[Run], day: u8, part: Option<u8>) -> impl Iterator<Item = (&Run, &Record)> {
+    runs.iter()
+        .flat_map(|run| run.records.iter().map(move |record| (run, record)))
+        .filter(move |(_, record)| record.day == day && part.is_none_or(|part| record.part == part))
+}
+
+/// The start of an input's hash, enough to tell inputs apart.
+fn input_cell(input: Option<&str>) -> String {
+    input.map_or_else(|| "-".to_string(), |input| input[..input.len().min(8)].to_string())
+}
+
+/// A short commit hash, keeping any `-dirty` mark.
+fn commit_cell(commit: Option<&str>) -> String {
+    let Some(commit) = commit else { return "-".to_string() };
+    let (hash, dirty) = commit.split_once('-').unwrap_or((commit, ""));
+    let short = &hash[..hash.len().min(8)];
+    if dirty.is_empty() { short.to_string() } else { format!("{short}-{dirty}") }
+}
+
+/// Seconds since the Unix epoch as a UTC date and time, e.g. `2022-12-08 14:03`.
+fn when(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let minutes = seconds % 86_400 / 60;
+
+    // Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02} {:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(recorded: u64, commit: &str, records: Vec<Record>) -> Run {
+        Run { recorded, commit: Some(commit.to_string()), command: "all".to_string(), records }
+    }
+
+    fn record(part: u8, answer: i64, elapsed_ms: u64) -> Record {
+        Record {
+            day: 8,
+            part,
+            solver: None,
+            answer: Some(Answer::Integer(answer)),
+            elapsed_ns: elapsed_ms * 1_000_000,
+            status: "ok".to_string(),
+            cached: false,
+            input: Some(REAL.to_string()),
+        }
+    }
+
+    /// Hashes of day 8's real input, and of its example.
+    const REAL: &str = "5e4d3c2b1a0f9e8d7c6b5a4f3e2d1c0b";
+    const EXAMPLE: &str = "e3b0c44298fc1c149afbf4c8996fb924";
+
+    /// Day 8 going multi-threaded, with a wrong answer on the way.
+    fn history() -> Vec<Run> {
+        let day_1 = Record { day: 1, ..record(1, 24000, 1) };
+        let cached = Record { cached: true, ..record(1, 21, 0) };
+        let failed = Record { answer: None, status: "panicked: oops".to_string(), ..record(2, 0, 9) };
+        vec![
+            run(1_670_457_600, "1a2b3c4d5e6f", vec![day_1, record(1, 21, 40), record(2, 8, 50)]),
+            run(1_670_461_200, "1a2b3c4d5e6f", vec![cached, failed]),
+            run(1_670_544_000, "9f8e7d6c-dirty", vec![record(1, 20, 10), record(2, 8, 20)]),
+            run(1_670_547_600, "0a0b0c0d", vec![record(1, 21, 12)]),
+        ]
+    }
+
+    #[test]
+    fn append_and_load() {
+        let path = std::env::temp_dir().join("runner_history").join("history.jsonl");
+        let _ = fs::remove_file(&path);
+        assert!(load(&path).unwrap().is_empty());
+
+        let runs = history();
+        for run in &runs {
+            append(&path, run).unwrap();
+        }
+        assert_eq!(load(&path).unwrap(), runs);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 4);
+
+        fs::write(&path, "{}\n").unwrap();
+        assert!(load(&path).unwrap_err().contains("line 1"));
+    }
+
+    #[test]
+    fn timing_trend() {
+        let expected = "\
+Part  When              Commit          Command  Solver    Input     Time     Change
+----  ----------------  --------------  -------  --------  --------  -------  ------
+   1  2022-12-08 00:00  1a2b3c4d        all      built-in  5e4d3c2b  40.00ms  -
+   2  2022-12-08 00:00  1a2b3c4d        all      built-in  5e4d3c2b  50.00ms  -
+   1  2022-12-09 00:00  9f8e7d6c-dirty  all      built-in  5e4d3c2b  10.00ms  -75.0%
+   2  2022-12-09 00:00  9f8e7d6c-dirty  all      built-in  5e4d3c2b  20.00ms  -60.0%
+   1  2022-12-09 01:00  0a0b0c0d        all      built-in  5e4d3c2b  12.00ms  +20.0%
+";
+        assert_eq!(trend(&history(), 8, None), expected);
+        assert_eq!(trend(&history(), 8, Some(2)).lines().count(), 4);
+    }
+
+    #[test]
+    fn changed_answers() {
+        let expected = "\
+Part  When              Commit          Solver    Input     Answer  Was
+----  ----------------  --------------  --------  --------  ------  ---
+   1  2022-12-08 00:00  1a2b3c4d        built-in  5e4d3c2b  21      -
+   2  2022-12-08 00:00  1a2b3c4d        built-in  5e4d3c2b  8       -
+   1  2022-12-09 00:00  9f8e7d6c-dirty  built-in  5e4d3c2b  20      21
+   1  2022-12-09 01:00  0a0b0c0d        built-in  5e4d3c2b  21      20
+";
+        assert_eq!(answer_changes(&history(), 8), expected);
+    }
+
+    #[test]
+    fn other_inputs_kept_apart() {
+        // A run on the example, between two on the real input
+        let example = Record { input: Some(EXAMPLE.to_string()), ..record(1, 13, 1) };
+        let runs = vec![
+            run(1_670_457_600, "1a2b3c4d", vec![record(1, 21, 40)]),
+            Run { command: "run".to_string(), ..run(1_670_461_200, "1a2b3c4d", vec![example]) },
+            run(1_670_544_000, "1a2b3c4d", vec![record(1, 21, 30)]),
+        ];
+
+        let expected = "\
+Part  When              Commit    Solver    Input     Answer  Was
+----  ----------------  --------  --------  --------  ------  ---
+   1  2022-12-08 00:00  1a2b3c4d  built-in  5e4d3c2b  21      -
+   1  2022-12-08 01:00  1a2b3c4d  built-in  e3b0c442  13      -
+";
+        assert_eq!(answer_changes(&runs, 8), expected);
+        let trend = trend(&runs, 8, None);
+        assert!(trend.lines().last().unwrap().ends_with("30.00ms  -25.0%"), "{trend}");
+    }
+}
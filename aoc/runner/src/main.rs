@@ -0,0 +1,711 @@
+use std::{path::PathBuf, process::ExitCode, time::{Duration, Instant}};
+
+use aoc::fetch::Fetcher;
+
+use clap::{Parser, Subcommand};
+
+mod baseline;
+mod batch;
+mod cache;
+mod check;
+mod counters;
+mod execute;
+mod external;
+mod history;
+mod pool;
+mod puzzle;
+mod registry;
+mod report;
+mod sandbox;
+mod serve;
+mod tui;
+
+/// Runs the Advent of Code solutions in this workspace.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Log more detail to stderr: -v for info, -vv for debug, -vvv for trace.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Extra log filter directives, e.g. `day_7=trace`. Adds to `RUST_LOG`.
+    #[arg(long, global = true)]
+    log: Option<String>,
+    /// Give up on a solution still running after this long, e.g. `30s`,
+    /// `500ms` or `2m`. `0` waits forever.
+    #[arg(long, global = true, default_value = "30s", value_parser = execute::parse_duration)]
+    timeout: Duration,
+    /// File registering team members' solvers in other languages, which run
+    /// alongside the built-in ones. Defaults to `solvers.txt` in the workspace.
+    /// Only read by the commands that run solutions.
+    #[arg(long, global = true)]
+    solvers: Option<PathBuf>,
+    /// Run external solvers without resource limits, except those given.
+    #[arg(long, global = true)]
+    no_limits: bool,
+    /// CPU time an external solver may use, e.g. `60s`. `0` for no limit.
+    #[arg(long, global = true, value_parser = execute::parse_duration)]
+    limit_cpu: Option<Duration>,
+    /// Memory (address space) an external solver may use, e.g. `4G`. `0` for
+    /// no limit.
+    #[arg(long, global = true, value_parser = sandbox::parse_size)]
+    limit_memory: Option<u64>,
+    /// Files an external solver may have open at once. `0` for no limit.
+    #[arg(long, global = true)]
+    limit_files: Option<u64>,
+    /// Processes and threads the user may have while an external solver
+    /// runs. Unlimited unless given, and never enforced for root.
+    #[arg(long, global = true)]
+    limit_processes: Option<u64>,
+    /// Run every solution, rather than answering those whose code and input
+    /// have not changed from the answer cache.
+    #[arg(long, global = true)]
+    no_cache: bool,
+    /// Time limit for one day, overriding `--timeout`, e.g. `11=2m`. Repeatable.
+    #[arg(long, global = true, value_name = "DAY=DURATION", value_parser = execute::parse_day_timeout)]
+    day_timeout: Vec<(u8, Duration)>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs every registered day concurrently and prints a summary table.
+    All {
+        /// Number of worker threads. Defaults to the number of CPUs.
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// Print results in calendar order rather than the order they finished.
+        #[arg(long)]
+        ordered: bool,
+        /// Folder containing the `day_N.txt` inputs.
+        #[arg(long)]
+        inputs: Option<PathBuf>,
+        /// Read answers drawn as pictures (e.g. day 10's CRT) as text.
+        #[arg(long)]
+        ocr: bool,
+    },
+    /// Runs every part of one day and prints its answers.
+    Run {
+        day: u8,
+        /// Input file. Defaults to the day's file in the inputs folder.
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Print the day's parsed input and final state as JSON instead.
+        #[arg(long)]
+        dump: bool,
+        /// Read answers drawn as pictures (e.g. day 10's CRT) as text.
+        #[arg(long)]
+        ocr: bool,
+    },
+    /// Checks inputs against the shape each day expects, and lists every
+    /// problem found with its line, without solving anything.
+    CheckInput {
+        /// Days to check. Defaults to every day.
+        days: Vec<u8>,
+        /// Folder containing the `day_N.txt` inputs.
+        #[arg(long)]
+        inputs: Option<PathBuf>,
+        /// Input file to check instead, for a single day.
+        #[arg(long, conflicts_with = "inputs")]
+        input: Option<PathBuf>,
+    },
+    /// Runs one day on every input in a folder, e.g. each team member's, and
+    /// checks the answers against `<input>.expected` sidecars where present.
+    Batch {
+        day: u8,
+        /// Folder of input files.
+        dir: PathBuf,
+        /// Number of worker threads. Defaults to the number of CPUs.
+        #[arg(short, long)]
+        jobs: Option<usize>,
+    },
+    /// Times each part over several runs and compares the medians with a
+    /// baseline recorded at an earlier commit.
+    Bench {
+        /// Days to time. Defaults to every day.
+        days: Vec<u8>,
+        /// Timed runs per part, after one untimed warm-up run.
+        #[arg(short, long, default_value_t = 10)]
+        samples: usize,
+        /// Folder containing the `day_N.txt` inputs. Timings are only
+        /// compared with baselines of the same inputs.
+        #[arg(long)]
+        inputs: Option<PathBuf>,
+        /// Record the timings as the baseline for the current commit.
+        #[arg(long)]
+        save: bool,
+        /// Commit to compare against. Defaults to the latest other baseline.
+        #[arg(long)]
+        against: Option<String>,
+        /// Warn when a part is this many percent slower than its baseline.
+        #[arg(long, default_value_t = 5.0)]
+        warn: f64,
+        /// Fail when a part is this many percent slower than its baseline.
+        #[arg(long, default_value_t = 15.0)]
+        fail: f64,
+        /// Baseline store. Defaults to `.bench/baselines.json` in the workspace.
+        #[arg(long)]
+        baselines: Option<PathBuf>,
+        /// Also count instructions and cache misses with the CPU's performance
+        /// counters, and compare by instructions, which barely vary between
+        /// runs. Falls back to time where the counters are unavailable.
+        #[arg(long)]
+        counters: bool,
+    },
+    /// Opens a dashboard of every day, to run them and see their answers,
+    /// timings and visualizations.
+    Tui {
+        /// Folder containing the `day_N.txt` inputs.
+        #[arg(long)]
+        inputs: Option<PathBuf>,
+    },
+    /// Serves the solutions over HTTP, to list the days and solve posted
+    /// inputs, e.g. from a web form.
+    Serve {
+        /// Address to listen on. Only this machine can connect by default.
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: String,
+        /// Requests answered at once. Defaults to the number of CPUs.
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// Largest input accepted, e.g. `1M`.
+        #[arg(long, default_value = "1M", value_parser = sandbox::parse_size)]
+        max_input: u64,
+        /// How long a client has to send its request, e.g. `10s`.
+        #[arg(long, default_value = "10s", value_parser = execute::parse_duration)]
+        read_timeout: Duration,
+        /// Timed-out solutions left running before built-in solutions are
+        /// refused. Their threads can't be stopped, so each uses a CPU.
+        #[arg(long, default_value_t = 4)]
+        max_abandoned: usize,
+    },
+    /// Shows how a day's timings and answers changed over the runs logged in
+    /// the history, those of `all`, `run` and `bench`, comparing only runs on
+    /// the same input.
+    History {
+        #[command(subcommand)]
+        query: HistoryQuery,
+    },
+    /// Lists or clears the answers remembered between runs.
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Converts a day's puzzle page to Markdown (e.g. for the book), and saves
+    /// its examples as fixtures beside the day's crate.
+    Puzzle {
+        day: u8,
+        /// Event year.
+        #[arg(long, default_value_t = 2022)]
+        year: u16,
+        /// Read the puzzle page from this HTML file instead of downloading it.
+        #[arg(long)]
+        html: Option<PathBuf>,
+        /// Download from this site instead of `AOC_BASE_URL` or the real one.
+        #[arg(long, conflicts_with = "html")]
+        base_url: Option<String>,
+        /// Write the Markdown to this file as well as the cache.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryQuery {
+    /// Lists each time a day was worked out, with how its time changed.
+    Trend {
+        day: u8,
+        /// Only this part.
+        #[arg(long)]
+        part: Option<u8>,
+    },
+    /// Lists each time a day's answers changed.
+    Answers {
+        day: u8,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Lists the remembered answers.
+    List,
+    /// Forgets the remembered answers.
+    Clear {
+        /// Days to forget. Defaults to every day.
+        days: Vec<u8>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    if let Err(e) = aoc::logging::init(cli.verbose, cli.log.as_deref()) {
+        eprintln!("Invalid log filter: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    let default_timeout = Some(cli.timeout).filter(|timeout| !timeout.is_zero());
+    let timeouts = cli.day_timeout.iter()
+        .fold(execute::Timeouts::new(default_timeout), |timeouts, &(day, timeout)| {
+            timeouts.with_day(day, timeout)
+        });
+
+    let defaults = if cli.no_limits { sandbox::Limits::NONE } else { sandbox::Limits::default() };
+    let limits = sandbox::Limits {
+        cpu: cli.limit_cpu.map_or(defaults.cpu, |cpu| Some(cpu).filter(|cpu| !cpu.is_zero())),
+        memory: cli.limit_memory.map_or(defaults.memory, |bytes| Some(bytes).filter(|&n| n > 0)),
+        open_files: cli.limit_files.map_or(defaults.open_files, |files| Some(files).filter(|&n| n > 0)),
+        processes: cli.limit_processes.map_or(defaults.processes, |processes| Some(processes).filter(|&n| n > 0)),
+    };
+
+    let mut solutions = registry::solutions();
+    // Only the commands that solve puzzles need the external solvers
+    let solves = matches!(cli.command,
+        Command::All { .. } | Command::Run { .. } | Command::Batch { .. }
+        | Command::Bench { .. } | Command::Tui { .. } | Command::Serve { .. });
+    if solves {
+        let solvers = cli.solvers.unwrap_or_else(registry::default_solvers_path);
+        match external::load(&solvers, limits) {
+            Ok(external) => solutions.extend(external),
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    match cli.command {
+        Command::All { jobs, ordered, inputs, ocr } => {
+            let jobs = jobs.unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, |n| n.get())
+            });
+            let inputs = inputs.unwrap_or_else(registry::default_inputs_dir);
+
+            let mut cache = if cli.no_cache {
+                None
+            } else {
+                match cache::Cache::load(&registry::default_answers_path()) {
+                    Ok(cache) => Some(cache),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        return ExitCode::FAILURE;
+                    }
+                }
+            };
+
+            let start = Instant::now();
+            let mut outcomes = match &mut cache {
+                Some(cache) => {
+                    let runs = solutions.iter()
+                        .map(|solution| ((), solution.clone(), registry::input_path(&inputs, solution.day)))
+                        .collect();
+                    cache.solve_with(runs, |runs| execute::solve_many(runs, jobs, &timeouts))
+                        .into_iter()
+                        .map(|((), outcome)| outcome)
+                        .collect()
+                }
+                None => execute::solve_all(&solutions, &inputs, jobs, &timeouts),
+            };
+            let wall = start.elapsed();
+            record_history("all", &outcomes, |day| registry::input_path(&inputs, day));
+            if let Some(cache) = &cache {
+                if let Err(e) = cache.save(&registry::default_answers_path()) {
+                    eprintln!("{e}");
+                }
+            }
+
+            if ordered {
+                outcomes.sort_by_key(|outcome| (outcome.day, outcome.part));
+            }
+
+            if ocr {
+                for outcome in &mut outcomes {
+                    outcome.answer = outcome.answer.take()
+                        .map(|answer| answer.read_with(aoc::answer::ocr));
+                }
+            }
+
+            print!("{}", report::table(&outcomes));
+            let threads = if jobs == 1 { "thread" } else { "threads" };
+            println!("\n{} in {:.2?} on {} {}", report::summary(&outcomes), wall, jobs, threads);
+
+            if outcomes.iter().all(|outcome| outcome.is_ok()) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Command::Run { day, input, dump, ocr } => {
+            let input = input.unwrap_or_else(|| {
+                registry::input_path(&registry::default_inputs_dir(), day)
+            });
+
+            if dump {
+                let Some(dump) = registry::dump(day) else {
+                    eprintln!("There is no solution for day {day}");
+                    return ExitCode::FAILURE;
+                };
+                if !input.is_file() {
+                    eprintln!("Missing input {}", input.display());
+                    return ExitCode::FAILURE;
+                }
+                let json = dump(&input.to_string_lossy());
+                println!("{}", serde_json::to_string_pretty(&json).unwrap());
+                return ExitCode::SUCCESS;
+            }
+
+            let solutions = solutions.into_iter()
+                .filter(|solution| solution.day == day)
+                .collect::<Vec<_>>();
+            if solutions.is_empty() {
+                eprintln!("There is no solution for day {day}");
+                return ExitCode::FAILURE;
+            }
+
+            let runs = solutions.into_iter()
+                .enumerate()
+                .map(|(i, solution)| (i, solution, input.clone()))
+                .collect::<Vec<_>>();
+            let outcomes = if cli.no_cache {
+                execute::solve_each(runs, &timeouts)
+            } else {
+                let path = registry::default_answers_path();
+                let mut cache = match cache::Cache::load(&path) {
+                    Ok(cache) => cache,
+                    Err(e) => {
+                        eprintln!("{e}");
+                        return ExitCode::FAILURE;
+                    }
+                };
+                let mut outcomes = cache.solve_with(runs, |runs| execute::solve_each(runs, &timeouts));
+                if let Err(e) = cache.save(&path) {
+                    eprintln!("{e}");
+                }
+                outcomes.sort_by_key(|(i, _)| *i);
+                outcomes
+            };
+            let mut outcomes = outcomes.into_iter().map(|(_, outcome)| outcome).collect::<Vec<_>>();
+            record_history("run", &outcomes, |_| input.clone());
+            if ocr {
+                for outcome in &mut outcomes {
+                    outcome.answer = outcome.answer.take()
+                        .map(|answer| answer.read_with(aoc::answer::ocr));
+                }
+            }
+
+            print!("{}", report::table(&outcomes));
+            if outcomes.iter().all(|outcome| outcome.is_ok()) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Command::CheckInput { days, inputs, input } => {
+            let mut days = days;
+            if days.is_empty() {
+                days = solutions.iter()
+                    .filter(|solution| solution.is_built_in())
+                    .map(|solution| solution.day)
+                    .collect();
+                days.dedup();
+            }
+            let inputs = inputs.unwrap_or_else(registry::default_inputs_dir);
+            let checked = match (&input, days.as_slice()) {
+                (Some(input), &[day]) => vec![check::check(day, input)],
+                (Some(_), _) => {
+                    eprintln!("Give a single day to check --input against");
+                    return ExitCode::FAILURE;
+                }
+                (None, days) => days.iter()
+                    .map(|&day| check::check(day, &registry::input_path(&inputs, day)))
+                    .collect(),
+            };
+
+            print!("{}", check::report(&checked));
+            if checked.iter().all(check::Checked::is_ok) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Command::Batch { day, dir, jobs } => {
+            let jobs = jobs.unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, |n| n.get())
+            });
+            let solutions = solutions.into_iter()
+                .filter(|solution| solution.day == day)
+                .collect::<Vec<_>>();
+            if solutions.is_empty() {
+                eprintln!("There is no solution for day {day}");
+                return ExitCode::FAILURE;
+            }
+
+            let start = Instant::now();
+            let runs = match batch::run(&solutions, &dir, jobs, &timeouts) {
+                Ok(runs) => runs,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let wall = start.elapsed();
+
+            print!("{}", batch::table(&runs));
+            println!("\n{} in {:.2?}", batch::summary(&runs), wall);
+
+            if runs.iter().all(|run| run.is_ok()) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Command::Bench { days, samples, inputs, save, against, warn, fail, baselines, counters } => {
+            let inputs = inputs.unwrap_or_else(registry::default_inputs_dir);
+            let path = baselines.unwrap_or_else(registry::default_baselines_path);
+            let solutions = solutions.into_iter()
+                .filter(|solution| days.is_empty() || days.contains(&solution.day))
+                .collect::<Vec<_>>();
+            if solutions.is_empty() {
+                eprintln!("There is no solution for the days given");
+                return ExitCode::FAILURE;
+            }
+
+            let mut store = match baseline::Store::load(&path) {
+                Ok(store) => store,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let commit = baseline::current_commit().unwrap_or_else(|| "unknown".to_string());
+            let base = match &against {
+                Some(against) => match store.find(against) {
+                    Some(base) => Some(base),
+                    None => {
+                        eprintln!("No baseline recorded for {against} in {}", path.display());
+                        return ExitCode::FAILURE;
+                    }
+                },
+                None => store.latest_except(&commit),
+            };
+
+            // One part at a time, so the timings do not compete for the CPU
+            let mut timings = vec![];
+            let mut failures = vec![];
+            let mut outcomes = vec![];
+            let mut counters = counters;
+            for solution in &solutions {
+                let input = registry::input_path(&inputs, solution.day);
+                let timeout = timeouts.get(solution.day);
+                let mut elapsed = vec![];
+                let mut counted = vec![];
+                let mut last = None;
+                for run in 0..=samples.max(1) {
+                    let (outcome, counts) = execute::solve_counted(solution.clone(), &input, timeout, counters);
+                    if !outcome.is_ok() {
+                        outcomes.push(outcome.clone());
+                        failures.push(outcome);
+                        break;
+                    }
+                    match counts {
+                        Some(Err(e)) => {
+                            eprintln!("Performance counters unavailable, timing only: {e}");
+                            counters = false;
+                        }
+                        Some(Ok(counts)) if run > 0 => counted.push(counts),
+                        _ => {}
+                    }
+                    if run > 0 {
+                        elapsed.push(outcome.elapsed);
+                    }
+                    last = Some(outcome);
+                }
+                if elapsed.len() == samples.max(1) {
+                    let mut timing = baseline::Timing::from_samples(solution.day, solution.part, &elapsed);
+                    if !solution.is_built_in() {
+                        timing.solver = Some(solution.name().to_string());
+                    }
+                    if counters && counted.len() == elapsed.len() {
+                        timing = timing.with_counts(&counted);
+                    }
+                    timing.input = cache::input_hash(&input);
+                    // Logged with its median time, like the baseline
+                    if let Some(outcome) = last {
+                        outcomes.push(report::Outcome { elapsed: Duration::from_nanos(timing.median_ns), ..outcome });
+                    }
+                    timings.push(timing);
+                }
+            }
+
+            record_history("bench", &outcomes, |day| registry::input_path(&inputs, day));
+
+            let thresholds = baseline::Thresholds { warn, fail };
+            let comparisons = timings.iter()
+                .map(|timing| {
+                    let previous = base.and_then(|base| base.matching(timing));
+                    baseline::compare(timing, previous, thresholds)
+                })
+                .collect::<Vec<_>>();
+
+            match base {
+                Some(base) => println!("Compared with {}\n", base.commit),
+                None => println!("No baseline to compare with\n"),
+            }
+            print!("{}", baseline::table(&comparisons));
+            if !failures.is_empty() {
+                println!();
+                print!("{}", report::table(&failures));
+            }
+
+            if save {
+                store.record(baseline::Baseline::new(&commit, timings));
+                if let Err(e) = store.save(&path) {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
+                }
+                println!("\nSaved as the baseline for {commit}");
+            }
+
+            let regressed = comparisons.iter()
+                .any(|comparison| comparison.verdict == baseline::Verdict::Regressed);
+            if regressed || !failures.is_empty() {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Command::Tui { inputs } => {
+            let inputs = inputs.unwrap_or_else(registry::default_inputs_dir);
+            let app = tui::App::new(solutions, registry::visualizations(), inputs, timeouts);
+            match tui::run(app) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("Terminal error: {e}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::Serve { address, jobs, max_input, read_timeout, max_abandoned } => {
+            let jobs = jobs.unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, |n| n.get())
+            });
+            let listener = match std::net::TcpListener::bind(&address) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    eprintln!("Could not listen on {address}: {e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            println!("Serving on http://{}", listener.local_addr().map_or(address, |address| address.to_string()));
+
+            let service = serve::Service {
+                solutions,
+                timeouts,
+                max_input,
+                read_timeout,
+                max_abandoned,
+                abandoned: execute::Abandoned::default(),
+            };
+            match serve::serve(listener, service, jobs) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("Server error: {e}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::History { query } => {
+            let path = registry::default_history_path();
+            let runs = match history::load(&path) {
+                Ok(runs) => runs,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let day = match query {
+                HistoryQuery::Trend { day, .. } | HistoryQuery::Answers { day } => day,
+            };
+            if !runs.iter().any(|run| run.records.iter().any(|record| record.day == day)) {
+                println!("No runs of day {day} in {}", path.display());
+                return ExitCode::SUCCESS;
+            }
+            match query {
+                HistoryQuery::Trend { day, part } => print!("{}", history::trend(&runs, day, part)),
+                HistoryQuery::Answers { day } => print!("{}", history::answer_changes(&runs, day)),
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Cache { action } => {
+            let path = registry::default_answers_path();
+            let mut cache = match cache::Cache::load(&path) {
+                Ok(cache) => cache,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            match action {
+                CacheAction::List => {
+                    if cache.entries.is_empty() {
+                        println!("No answers remembered in {}", path.display());
+                    } else {
+                        print!("{}", cache::table(&cache.entries));
+                    }
+                }
+                CacheAction::Clear { days } => {
+                    let cleared = cache.clear(&days);
+                    if let Err(e) = cache.save(&path) {
+                        eprintln!("{e}");
+                        return ExitCode::FAILURE;
+                    }
+                    println!("Forgot {cleared} answers");
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Puzzle { day, year, html, base_url, output } => {
+            let cache_dir = registry::default_cache_dir();
+            let mut fetcher = Fetcher::new(&cache_dir);
+            if let Some(base_url) = base_url {
+                fetcher = fetcher.with_base_url(&base_url);
+            }
+            let source = match &html {
+                Some(path) => puzzle::Source::File(path),
+                None => puzzle::Source::Fetch(&fetcher),
+            };
+
+            let puzzle = match puzzle::import(source, year, day, &cache_dir, &registry::fixtures_dir(day)) {
+                Ok(puzzle) => puzzle,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            match output {
+                Some(path) => {
+                    if let Err(e) = std::fs::write(&path, &puzzle.markdown) {
+                        eprintln!("Could not write {}: {e}", path.display());
+                        return ExitCode::FAILURE;
+                    }
+                }
+                None => print!("{}", puzzle.markdown),
+            }
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Logs a run's outcomes in the history. A history that cannot be written is
+/// only worth a warning.
+/// Logs the `outcomes` of `command` in the history, each with the hash of the
+/// input its day ran on.
+fn record_history(command: &str, outcomes: &[report::Outcome], input: impl Fn(u8) -> PathBuf) {
+    let records = outcomes.iter()
+        .map(|outcome| history::Record::new(outcome, cache::input_hash(&input(outcome.day))))
+        .collect();
+    let run = history::Run::new(command, baseline::current_commit(), records);
+    if let Err(e) = history::append(&registry::default_history_path(), &run) {
+        eprintln!("Warning: {e}");
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// A fixed number of worker threads that pull jobs off a shared channel.
+pub struct ThreadPool {
+    workers: Vec<thread::JoinHandle<()>>,
+    sender: Option<mpsc::Sender<Job>>,
+}
+
+impl ThreadPool {
+    /// Spawns `size` workers. A pool always has at least one worker.
+    pub fn new(size: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+
+        // Every worker needs to receive from the same channel, but only one of
+        // them should take any given job, so the receiver is shared behind a
+        // lock.
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..size.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || loop {
+                    // The lock is released as soon as a job is taken, so other
+                    // workers can receive while this one is busy.
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        // The pool was dropped and the queue is empty.
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+
+        Self { workers, sender: Some(sender) }
+    }
+
+    /// Queues `job` to be run by the next free worker.
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.sender.as_ref()
+            .expect("Pool is only shut down when dropped")
+            .send(Box::new(job))
+            .expect("Workers only exit once the pool is dropped");
+    }
+}
+
+impl Drop for ThreadPool {
+    /// Lets the workers finish every queued job, then waits for them to exit.
+    fn drop(&mut self) {
+        // Required for the workers' loops to finish
+        drop(self.sender.take());
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_every_job() {
+        let (tx, rx) = mpsc::channel();
+        let pool = ThreadPool::new(3);
+        for i in 0..10 {
+            let tx = tx.clone();
+            pool.execute(move || tx.send(i).unwrap());
+        }
+        drop(tx);
+        drop(pool);
+
+        let mut results = rx.iter().collect::<Vec<_>>();
+        results.sort();
+        assert_eq!(results, (0..10).collect::<Vec<_>>());
+    }
+}
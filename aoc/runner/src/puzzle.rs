@@ -0,0 +1,157 @@
+use std::{fs, path::Path, time::Duration};
+
+use aoc::{fetch::Fetcher, puzzle::Puzzle};
+
+/// How old a downloaded page without part two may be before it is fetched
+/// again, in case part one has been solved since.
+const PART_ONE_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+/// Where a puzzle page is read from.
+pub enum Source<'a> {
+    /// Downloaded through the fetcher, which keeps a copy of the page
+    Fetch(&'a Fetcher),
+    /// A page saved by hand
+    File(&'a Path),
+}
+
+/// Reads the page for `day` of `year`, converts it, and saves the Markdown to
+/// `cache_dir/{year}_day_{day}.md` and the examples to `fixtures_dir`.
+pub fn import(
+    source: Source,
+    year: u16,
+    day: u8,
+    cache_dir: &Path,
+    fixtures_dir: &Path,
+) -> Result<Puzzle, String> {
+    let puzzle = match source {
+        Source::Fetch(fetcher) => {
+            let path = format!("/{year}/day/{day}");
+            let download = |max_age| fetcher.get_fresh_within(&path, max_age)
+                .map(|html| Puzzle::from_html(&html))
+                .map_err(|e| format!("Could not download puzzle: {e}"));
+            match download(Duration::MAX)? {
+                puzzle if puzzle.has_part_two() => puzzle,
+                _ => download(PART_ONE_MAX_AGE)?,
+            }
+        }
+        Source::File(path) => fs::read_to_string(path)
+            .map(|html| Puzzle::from_html(&html))
+            .map_err(|e| format!("Could not read {}: {e}", path.display()))?,
+    };
+
+    if puzzle.title.is_empty() {
+        return Err(format!("No puzzle description found for day {day} of {year}"));
+    }
+
+    let markdown_path = cache_dir.join(format!("{year}_day_{day}.md"));
+    fs::create_dir_all(cache_dir)
+        .and_then(|()| fs::write(&markdown_path, &puzzle.markdown))
+        .map_err(|e| format!("Could not write {}: {e}", markdown_path.display()))?;
+
+    let examples = puzzle.write_examples(fixtures_dir)
+        .map_err(|e| format!("Could not write fixtures to {}: {e}", fixtures_dir.display()))?;
+    tracing::info!(day, markdown = %markdown_path.display(), examples = examples.len(), "imported puzzle");
+
+    Ok(puzzle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        path::PathBuf,
+        thread,
+    };
+
+    const PAGE: &str = "<html><body><main>\
+        <article class=\"day-desc\"><h2>--- Day 6: Tuning Trouble ---</h2>\
+        <p>For example:</p><pre><code>mjqjpqmgbljsphdztnvjfqwrcgsmlb</code></pre>\
+        </article></main></body></html>";
+
+    /// Answers a single request for `/2022/day/6` with `PAGE`.
+    fn stand_in() -> (String, thread::JoinHandle<()>) {
+        stand_in_with(PAGE)
+    }
+
+    fn stand_in_with(page: &'static str) -> (String, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = BufReader::new(&stream).lines().next().unwrap().unwrap();
+            assert_eq!(request, "GET /2022/day/6 HTTP/1.1");
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", page.len(), page).unwrap();
+        });
+        (url, handle)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn import_from_stand_in() {
+        let (url, server) = stand_in();
+        let cache = temp_dir("runner_puzzle_cache");
+        let fixtures = temp_dir("runner_puzzle_fixtures");
+        let fetcher = Fetcher::new(&cache).with_base_url(&url);
+
+        let puzzle = import(Source::Fetch(&fetcher), 2022, 6, &cache, &fixtures).unwrap();
+        assert_eq!(puzzle.title, "Day 6: Tuning Trouble");
+        assert_eq!(
+            fs::read_to_string(fixtures.join("example_1.txt")).unwrap(),
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n",
+        );
+        assert_eq!(fs::read_to_string(cache.join("2022_day_6.md")).unwrap(), puzzle.markdown);
+
+        // The stand-in has stopped, so this must come from the cache.
+        server.join().unwrap();
+        let again = import(Source::Fetch(&fetcher), 2022, 6, &cache, &fixtures).unwrap();
+        assert_eq!(again, puzzle);
+    }
+
+    #[test]
+    fn refetch_for_part_two() {
+        let cache = temp_dir("runner_puzzle_part_two");
+        let fixtures = temp_dir("runner_puzzle_part_two_fixtures");
+
+        // A copy from before part one was solved, long enough ago
+        let (url, server) = stand_in();
+        let fetcher = Fetcher::new(&cache).with_base_url(&url);
+        import(Source::Fetch(&fetcher), 2022, 6, &cache, &fixtures).unwrap();
+        server.join().unwrap();
+        let hour_ago = std::time::SystemTime::now() - Duration::from_secs(60 * 60);
+        let cached = fs::read_dir(&cache).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.extension().is_none())
+            .unwrap();
+        fs::File::options().write(true).open(cached).unwrap().set_modified(hour_ago).unwrap();
+
+        const SOLVED: &str = "<html><body><main>\
+            <article class=\"day-desc\"><h2>--- Day 6: Tuning Trouble ---</h2></article>\
+            <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2></article>\
+            </main></body></html>";
+        let (url, server) = stand_in_with(SOLVED);
+        let fetcher = Fetcher::new(&cache).with_base_url(&url);
+        assert!(import(Source::Fetch(&fetcher), 2022, 6, &cache, &fixtures).unwrap().has_part_two());
+        server.join().unwrap();
+
+        // Now complete, so kept however old it gets
+        assert!(import(Source::Fetch(&fetcher), 2022, 6, &cache, &fixtures).unwrap().has_part_two());
+    }
+
+    #[test]
+    fn not_a_puzzle() {
+        let dir = temp_dir("runner_puzzle_not_a_puzzle");
+        fs::create_dir_all(&dir).unwrap();
+        let page = dir.join("page.html");
+        fs::write(&page, "<html><body>404 Not Found</body></html>").unwrap();
+
+        let result = import(Source::File(&page), 2022, 6, &dir, &dir.join("fixtures"));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use aoc::{schema::Schema, Answer};
+
+use crate::external::External;
+
+/// A single part of a day's puzzle that the runner knows how to solve.
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    pub solver: Solver,
+}
+
+/// What solves a part.
+#[derive(Debug, Clone)]
+pub enum Solver {
+    /// One of the day crates in this workspace. Takes the path to the day's
+    /// input and returns the answer.
+    BuiltIn(fn(&str) -> Answer),
+    /// A program registered in the solvers file, shared by every part it solves.
+    External(Arc<External>),
+}
+
+impl Solution {
+    pub const fn new(day: u8, part: u8, solve: fn(&str) -> Answer) -> Self {
+        Self { day, part, solver: Solver::BuiltIn(solve) }
+    }
+
+    /// Name of the external solver, or `built-in`.
+    pub fn name(&self) -> &str {
+        match &self.solver {
+            Solver::BuiltIn(_) => "built-in",
+            Solver::External(external) => &external.name,
+        }
+    }
+
+    /// Whether this is one of the day crates in the workspace.
+    pub fn is_built_in(&self) -> bool {
+        matches!(self.solver, Solver::BuiltIn(_))
+    }
+
+    /// A version of the solution's code, which changes whenever the code
+    /// does. `None` for external solvers, whose programs may change at any
+    /// time without the runner knowing.
+    pub fn version(&self) -> Option<&'static str> {
+        match self.solver {
+            Solver::BuiltIn(_) => version(self.day),
+            Solver::External(_) => None,
+        }
+    }
+}
+
+/// Every solution in the workspace, in calendar order.
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(1, 1, day_1::part_one),
+        Solution::new(1, 2, day_1::part_two),
+        Solution::new(2, 1, day_2::part_one),
+        Solution::new(3, 1, day_3::part_one),
+        Solution::new(4, 1, day_4::part_one),
+        Solution::new(5, 1, day_5::part_one),
+        Solution::new(6, 1, day_6::part_one),
+        Solution::new(7, 1, day_7::part_one),
+        Solution::new(8, 1, day_8::part_one),
+        Solution::new(9, 1, day_9::part_one),
+        Solution::new(10, 1, day_10::part_one),
+        Solution::new(10, 2, day_10::part_two),
+        Solution::new(11, 1, day_11::part_one),
+        Solution::new(11, 2, day_11::part_two),
+    ]
+}
+
+/// A picture of a day's puzzle, drawn as text from the day's input.
+#[derive(Debug, Clone, Copy)]
+pub struct Visualization {
+    pub day: u8,
+    pub name: &'static str,
+    /// Takes the path to the day's input and returns the picture.
+    pub render: fn(&str) -> String,
+}
+
+/// Every visualization in the workspace, in calendar order.
+pub fn visualizations() -> Vec<Visualization> {
+    vec![
+        Visualization {
+            day: 5,
+            name: "port",
+            render: |path| day_5::arrange_port(path).to_string(),
+        },
+        Visualization {
+            day: 7,
+            name: "filesystem",
+            render: |path| day_7::build_filesystem(path).to_string(),
+        },
+        Visualization {
+            day: 10,
+            name: "screen",
+            render: |path| day_10::run_program(path).screen().join("\n"),
+        },
+    ]
+}
+
+/// Takes the path to a day's input and returns its parsed input and final
+/// state as JSON.
+pub type Dump = fn(&str) -> serde_json::Value;
+
+/// The dump for `day`, if that day exists.
+pub fn dump(day: u8) -> Option<Dump> {
+    let dump: Dump = match day {
+        1 => day_1::dump,
+        2 => day_2::dump,
+        3 => day_3::dump,
+        4 => day_4::dump,
+        5 => day_5::dump,
+        6 => day_6::dump,
+        7 => day_7::dump,
+        8 => day_8::dump,
+        9 => day_9::dump,
+        10 => day_10::dump,
+        11 => day_11::dump,
+        _ => return None,
+    };
+    Some(dump)
+}
+
+/// The shape of `day`'s input, if that day exists. See `aoc::schema`.
+pub fn schema(day: u8) -> Option<Schema> {
+    let schema = match day {
+        1 => day_1::schema(),
+        2 => day_2::schema(),
+        3 => day_3::schema(),
+        4 => day_4::schema(),
+        5 => day_5::schema(),
+        6 => day_6::schema(),
+        7 => day_7::schema(),
+        8 => day_8::schema(),
+        9 => day_9::schema(),
+        10 => day_10::schema(),
+        11 => day_11::schema(),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+/// Where the runner looks for puzzle inputs when none is given: the same
+/// `aoc/inputs` folder the individual days read from.
+pub fn default_inputs_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("inputs")
+}
+
+/// Path of the input file for `day` inside `inputs_dir`, e.g. `day_5.txt`.
+pub fn input_path(inputs_dir: &Path, day: u8) -> PathBuf {
+    inputs_dir.join(format!("day_{day}.txt"))
+}
+
+/// The version of `day`'s crate, from a hash of its source and the shared
+/// library's taken at build time.
+fn version(day: u8) -> Option<&'static str> {
+    let version = match day {
+        1 => env!("AOC_DAY_1_VERSION"),
+        2 => env!("AOC_DAY_2_VERSION"),
+        3 => env!("AOC_DAY_3_VERSION"),
+        4 => env!("AOC_DAY_4_VERSION"),
+        5 => env!("AOC_DAY_5_VERSION"),
+        6 => env!("AOC_DAY_6_VERSION"),
+        7 => env!("AOC_DAY_7_VERSION"),
+        8 => env!("AOC_DAY_8_VERSION"),
+        9 => env!("AOC_DAY_9_VERSION"),
+        10 => env!("AOC_DAY_10_VERSION"),
+        11 => env!("AOC_DAY_11_VERSION"),
+        _ => return None,
+    };
+    Some(version)
+}
+
+/// Where downloaded pages are kept between runs.
+pub fn default_cache_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(".cache")
+}
+
+/// Where `bench` keeps its baselines. Timings only compare on the machine they
+/// were taken on, so this is ignored by git.
+pub fn default_baselines_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(".bench").join("baselines.json")
+}
+
+/// Where answers are remembered between runs. See `cache`.
+pub fn default_answers_path() -> PathBuf {
+    default_cache_dir().join("answers.json")
+}
+
+/// Where every run's answers and timings are logged. Like the baselines, this
+/// is particular to the machine, so it is ignored by git.
+pub fn default_history_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(".history.jsonl")
+}
+
+/// Where external solvers are registered. See `external`.
+pub fn default_solvers_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("solvers.txt")
+}
+
+/// Folder beside `day`'s crate that holds its example inputs.
+pub fn fixtures_dir(day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(format!("day_{day}")).join("fixtures")
+}
@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use aoc::Answer;
+
+/// How a single solution run ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// The day's input file does not exist, so the solution was not run.
+    MissingInput,
+    /// The solution panicked. Holds the panic message.
+    Panicked(String),
+    /// The solution was still running when its time ran out, and was
+    /// abandoned. Holds the time limit.
+    TimedOut(Duration),
+    /// An external solver exited with an error or broke the protocol.
+    Failed(String),
+    /// An external solver ran into a resource limit. Holds which one.
+    LimitExceeded(String),
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::MissingInput => write!(f, "missing input"),
+            Self::Panicked(message) => write!(f, "panicked: {message}"),
+            Self::TimedOut(timeout) => write!(f, "timed out after {timeout:.2?}"),
+            Self::Failed(message) => write!(f, "failed: {message}"),
+            Self::LimitExceeded(limit) => write!(f, "exceeded {limit} limit"),
+        }
+    }
+}
+
+/// The result of running one part of one day.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    /// Name of the external solver that ran, or `None` for a built-in one.
+    pub solver: Option<String>,
+    pub answer: Option<Answer>,
+    pub elapsed: Duration,
+    pub status: Status,
+    /// Whether the answer was remembered from an earlier run rather than
+    /// worked out. The time is then that run's.
+    pub cached: bool,
+}
+
+impl Outcome {
+    pub fn is_ok(&self) -> bool {
+        self.status == Status::Ok
+    }
+}
+
+/// Renders outcomes as a plain text table, one row per part, in the order
+/// they are given. Multi-line answers continue on the following rows. If any
+/// external solvers ran, a column says which solver each row is from.
+pub fn table(outcomes: &[Outcome]) -> String {
+    let rows = outcomes.iter()
+        .map(|outcome| [
+            outcome.day.to_string(),
+            outcome.part.to_string(),
+            solver_cell(outcome.solver.as_deref()),
+            answer_cell(outcome.answer.as_ref()),
+            format!("{:.2?}", outcome.elapsed),
+            if outcome.cached { format!("{} (cached)", outcome.status) } else { outcome.status.to_string() },
+        ])
+        .collect::<Vec<_>>();
+
+    if outcomes.iter().all(|outcome| outcome.solver.is_none()) {
+        let rows = rows.into_iter()
+            .map(|[day, part, _, answer, time, status]| [day, part, answer, time, status])
+            .collect::<Vec<_>>();
+        return render(["Day", "Part", "Answer", "Time", "Status"], &rows, 2);
+    }
+    render(["Day", "Part", "Solver", "Answer", "Time", "Status"], &rows, 2)
+}
+
+/// The name of the solver behind an outcome, as shown in a table cell.
+pub fn solver_cell(solver: Option<&str>) -> String {
+    solver.unwrap_or("built-in").to_string()
+}
+
+/// An answer as shown in a table cell, or `-` if there is none.
+pub fn answer_cell(answer: Option<&Answer>) -> String {
+    answer.map_or_else(|| "-".to_string(), Answer::to_string)
+}
+
+/// Lays out `rows` under `header` in aligned columns. The first
+/// `right_aligned` columns are right-aligned (numbers read better that way).
+/// A cell spanning several lines continues on the following rows, with the
+/// other cells left blank.
+pub fn render<const N: usize>(header: [&str; N], rows: &[[String; N]], right_aligned: usize) -> String {
+    // Each row becomes one or more lines of cells.
+    let mut lines: Vec<[&str; N]> = vec![];
+    for row in rows {
+        let cells = row.each_ref().map(|cell| cell.lines().collect::<Vec<_>>());
+        let height = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        for i in 0..height {
+            lines.push(cells.each_ref().map(|cell| cell.get(i).copied().unwrap_or_default()));
+        }
+    }
+
+    let mut widths = header.map(str::len);
+    for line in &lines {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut s = String::new();
+    let mut push_line = |cells: [&str; N]| {
+        let line = cells.iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, width))| if i < right_aligned {
+                format!("{cell:>width$}")
+            } else {
+                format!("{cell:<width$}")
+            })
+            .collect::<Vec<String>>()
+            .join("  ");
+        s.push_str(line.trim_end());
+        s.push('\n');
+    };
+
+    push_line(header);
+    push_line(widths.map(|width| "-".repeat(width)).each_ref().map(String::as_str));
+    for line in lines {
+        push_line(line);
+    }
+
+    s
+}
+
+/// One line tally of the outcomes, e.g. `13 parts: 11 ok, 1 failed, 1 timed out`.
+pub fn summary(outcomes: &[Outcome]) -> String {
+    let ok = outcomes.iter().filter(|outcome| outcome.is_ok()).count();
+    let timed_out = outcomes.iter()
+        .filter(|outcome| matches!(outcome.status, Status::TimedOut(_)))
+        .count();
+    format!(
+        "{} parts: {} ok, {} failed, {} timed out",
+        outcomes.len(),
+        ok,
+        outcomes.len() - ok - timed_out,
+        timed_out,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(day: u8, part: u8, answer: Option<Answer>, status: Status) -> Outcome {
+        Outcome {
+            day,
+            part,
+            solver: None,
+            answer,
+            elapsed: Duration::from_millis(3),
+            status,
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn table() {
+        let outcomes = [
+            outcome(1, 1, Some(24000_u32.into()), Status::Ok),
+            outcome(10, 2, Some(vec!["#..".into(), ".#.".into()].into()), Status::Ok),
+            outcome(11, 1, None, Status::Panicked("oops".into())),
+            outcome(11, 2, None, Status::TimedOut(Duration::from_secs(2))),
+        ];
+        let expected = "\
+Day  Part  Answer  Time    Status
+---  ----  ------  ------  ---------------------
+  1     1  24000   3.00ms  ok
+ 10     2  #..     3.00ms  ok
+           .#.
+ 11     1  -       3.00ms  panicked: oops
+ 11     2  -       3.00ms  timed out after 2.00s
+";
+        assert_eq!(super::table(&outcomes), expected);
+        assert_eq!(summary(&outcomes), "4 parts: 2 ok, 1 failed, 1 timed out");
+    }
+
+    #[test]
+    fn table_with_external_solvers() {
+        let mut external = outcome(5, 1, Some("CMZ".into()), Status::Failed("exit status: 1".into()));
+        external.solver = Some("alice".into());
+        let outcomes = [outcome(5, 1, Some("CMZ".into()), Status::Ok), external];
+        let expected = "\
+Day  Part  Solver    Answer  Time    Status
+---  ----  --------  ------  ------  ----------------------
+  5     1  built-in  CMZ     3.00ms  ok
+  5     1  alice     CMZ     3.00ms  failed: exit status: 1
+";
+        assert_eq!(super::table(&outcomes), expected);
+        assert_eq!(summary(&outcomes), "2 parts: 1 ok, 1 failed, 0 timed out");
+    }
+}
@@ -0,0 +1,164 @@
+//! Resource limits for external solvers, so a runaway program cannot take
+//! the machine down with it. On Linux they are applied with `setrlimit` in
+//! the child just before it starts the solver; elsewhere they are not
+//! enforced.
+
+use std::{process::{Command, ExitStatus}, time::Duration};
+
+/// Limits on the resources one solver process may use. `None` leaves that
+/// resource as it is for the runner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// CPU time, as opposed to wall-clock time. The process gets `SIGXCPU`,
+    /// then is killed a second later.
+    pub cpu: Option<Duration>,
+    /// Address space in bytes. Allocations beyond it fail.
+    pub memory: Option<u64>,
+    pub open_files: Option<u64>,
+    /// Processes for the user running the solver, not just the solver's
+    /// own: this is how the kernel counts them, threads included. Not
+    /// enforced for root, and off by default, as a limit low enough to
+    /// matter would stop threaded solvers from starting their threads.
+    pub processes: Option<u64>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            cpu: Some(Duration::from_secs(60)),
+            memory: Some(4 << 30),
+            open_files: Some(256),
+            processes: None,
+        }
+    }
+}
+
+impl Limits {
+    /// No limits at all.
+    pub const NONE: Self = Self { cpu: None, memory: None, open_files: None, processes: None };
+
+    /// Makes `command` apply the limits to the process it starts.
+    #[cfg(target_os = "linux")]
+    pub fn apply(&self, command: &mut Command) {
+        use std::os::unix::process::CommandExt;
+
+        let limits = [
+            (libc::RLIMIT_CPU, self.cpu.map(|cpu| cpu.as_secs().max(1))),
+            (libc::RLIMIT_AS, self.memory),
+            (libc::RLIMIT_NOFILE, self.open_files),
+            (libc::RLIMIT_NPROC, self.processes),
+        ];
+        // SAFETY: the closure only makes setrlimit calls, which are safe
+        // between fork and exec, and does not allocate
+        unsafe {
+            command.pre_exec(move || {
+                for (resource, limit) in limits {
+                    let Some(limit) = limit else { continue };
+                    // The CPU limit is soft, so the solver is told before it
+                    // is killed
+                    let hard = if resource == libc::RLIMIT_CPU { limit + 1 } else { limit };
+                    let rlimit = libc::rlimit { rlim_cur: limit as _, rlim_max: hard as _ };
+                    if libc::setrlimit(resource, &rlimit) == -1 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn apply(&self, _command: &mut Command) {}
+
+    /// Which limit a failed solver ran into, judging by the signal that
+    /// ended it and the CPU time it used, if known. `None` if it seems to
+    /// have failed of its own accord. The other limits make calls fail
+    /// rather than send a signal, so a solver that runs into them only fails
+    /// the way it fails for any other error.
+    pub fn exceeded(&self, status: ExitStatus, cpu_used: Option<Duration>) -> Option<&'static str> {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::unix::process::ExitStatusExt;
+
+            // In whole seconds, as `apply` sets it
+            let cpu = Duration::from_secs(self.cpu?.as_secs().max(1));
+            match status.signal() {
+                Some(libc::SIGXCPU) => Some("CPU time"),
+                // A solver that ignores SIGXCPU is killed at the hard limit,
+                // but only one that used up its time was killed for that
+                Some(libc::SIGKILL) if cpu_used.is_some_and(|used| used >= cpu) => Some("CPU time"),
+                _ => None,
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (status, cpu_used);
+            None
+        }
+    }
+}
+
+/// Parses a size in bytes such as `512M`, `4G`, `64K` or a bare number.
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (number, shift) = match text.char_indices().last() {
+        Some((at, 'K' | 'k')) => (&text[..at], 10),
+        Some((at, 'M' | 'm')) => (&text[..at], 20),
+        Some((at, 'G' | 'g')) => (&text[..at], 30),
+        _ => (text, 0),
+    };
+    number.trim().parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(1 << shift))
+        .ok_or_else(|| format!("invalid size {text:?}, expected e.g. `512M` or `4G`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("4096"), Ok(4096));
+        assert_eq!(parse_size("64K"), Ok(64 << 10));
+        assert_eq!(parse_size("512M"), Ok(512 << 20));
+        assert_eq!(parse_size("4g"), Ok(4 << 30));
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("1.5G").is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn recognise_violations() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let limits = Limits::default();
+        let (xcpu, killed) = (ExitStatus::from_raw(libc::SIGXCPU), ExitStatus::from_raw(libc::SIGKILL));
+        let failed = Command::new("sh").args(["-c", "exit 1"]).status().unwrap();
+        assert_eq!(limits.exceeded(xcpu, None), Some("CPU time"));
+        assert_eq!(limits.exceeded(killed, Some(Duration::from_secs(61))), Some("CPU time"));
+        // Killed early, or by someone else
+        assert_eq!(limits.exceeded(killed, Some(Duration::from_millis(100))), None);
+        assert_eq!(limits.exceeded(killed, None), None);
+        assert_eq!(limits.exceeded(failed, Some(Duration::from_secs(61))), None);
+        assert_eq!(Limits::NONE.exceeded(xcpu, None), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn limits_reach_the_solver() {
+        let limits = Limits {
+            cpu: Some(Duration::from_secs(7)),
+            memory: Some(256 << 20),
+            open_files: Some(32),
+            processes: None,
+        };
+        let mut command = Command::new("sh");
+        command.args(["-c", "ulimit -t; ulimit -v; ulimit -n"]);
+        limits.apply(&mut command);
+        let output = command.output().unwrap();
+        let limits = String::from_utf8(output.stdout).unwrap();
+        // `ulimit -v` is in kibibytes
+        assert_eq!(limits.lines().collect::<Vec<_>>(), ["7", "262144", "32"]);
+    }
+}
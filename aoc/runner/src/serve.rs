@@ -0,0 +1,458 @@
+//! A small HTTP service for solving pasted inputs, e.g. from a web form in a
+//! teaching session. It speaks just enough HTTP/1.1 for that, one request per
+//! connection:
+//!
+//! - `GET /days` lists the days and parts that can be solved, and by whom.
+//! - `POST /days/<day>/parts/<part>` solves the input in the request body and
+//!   returns the answer and timing as JSON. `?solver=<name>` picks an
+//!   external solver instead of the built-in one.
+//!
+//! Errors are JSON too, as `{"error": "..."}`.
+//!
+//! A built-in solution that times out can't be stopped, so its thread is left
+//! running. Once `max_abandoned` of them are, built-in solutions are refused
+//! with a 503 until some finish. External solvers are killed on timeout.
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use aoc::Answer;
+use serde_json::{json, Value};
+
+use crate::{
+    execute::{self, Abandoned, Timeouts},
+    pool::ThreadPool,
+    registry::Solution,
+    report::{Outcome, Status},
+};
+
+/// Most bytes allowed in the request line and headers together.
+const MAX_HEAD: u64 = 8 << 10;
+
+/// What the service solves, and how much it will put up with per request.
+#[derive(Debug, Clone)]
+pub struct Service {
+    pub solutions: Vec<Solution>,
+    /// Limits on how long each solution may run
+    pub timeouts: Timeouts,
+    /// Largest input accepted, in bytes
+    pub max_input: u64,
+    /// How long a client has to send its whole request
+    pub read_timeout: Duration,
+    /// Most timed-out built-in solutions left running before more are
+    /// refused. Their threads can't be stopped, so each one keeps using CPU.
+    pub max_abandoned: usize,
+    /// Timed-out built-in solutions still running
+    pub abandoned: Abandoned,
+}
+
+/// A response: its status code and JSON body.
+type Response = (u16, Value);
+
+/// Answers connections on `listener` forever, `jobs` at a time. Connections
+/// beyond that wait their turn.
+pub fn serve(listener: TcpListener, service: Service, jobs: usize) -> io::Result<()> {
+    let service = Arc::new(service);
+    let pool = ThreadPool::new(jobs);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!("could not accept a connection: {e}");
+                continue;
+            }
+        };
+        let service = Arc::clone(&service);
+        pool.execute(move || {
+            if let Err(e) = service.answer(stream) {
+                tracing::debug!("could not answer a request: {e}");
+            }
+        });
+    }
+    Ok(())
+}
+
+impl Service {
+    /// Reads one request from `stream` and writes the response.
+    fn answer(&self, stream: TcpStream) -> io::Result<()> {
+        let deadline = Instant::now() + self.read_timeout;
+        let mut reader = BufReader::new(Deadline { stream: stream.try_clone()?, deadline });
+        let (status, body) = match self.read_request(&mut reader) {
+            Ok(request) => {
+                let span = tracing::info_span!("request", method = %request.method, path = %request.path);
+                let _entered = span.enter();
+                let response = self.route(&request);
+                tracing::info!(status = response.0, "answered");
+                response
+            }
+            Err(response) => response,
+        };
+
+        let body = body.to_string();
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            reason(status),
+            body.len(),
+        )?;
+        stream.flush()?;
+
+        // Closing with some of the request unread would reset the connection,
+        // and the client might lose the response, so finish reading first
+        stream.shutdown(Shutdown::Write)?;
+        let _ = io::copy(&mut reader.take(self.max_input + MAX_HEAD), &mut io::sink());
+        Ok(())
+    }
+
+    /// Reads the request line, headers and body, or gives the error response
+    /// if the request is too big, too slow or not one we understand.
+    fn read_request(&self, reader: &mut impl BufRead) -> Result<Request, Response> {
+        let mut head = reader.by_ref().take(MAX_HEAD);
+        let mut line = String::new();
+        read_line(&mut head, &mut line)?;
+        let mut words = line.split_whitespace();
+        let (Some(method), Some(target), Some(_version)) = (words.next(), words.next(), words.next()) else {
+            return Err(error(400, "malformed request line"));
+        };
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let mut request = Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+            body: vec![],
+        };
+
+        let mut length = None;
+        loop {
+            line.clear();
+            read_line(&mut head, &mut line)?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            let Some((name, value)) = header.split_once(':') else {
+                return Err(error(400, "malformed header"));
+            };
+            if name.eq_ignore_ascii_case("content-length") {
+                let value = value.trim().parse::<u64>().map_err(|_| error(400, "invalid Content-Length"))?;
+                length = Some(value);
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                return Err(error(411, "chunked requests are not supported; send a Content-Length"));
+            }
+        }
+
+        match length {
+            Some(length) if length > self.max_input => {
+                return Err(error(413, &format!("input is {length} bytes; the limit is {}", self.max_input)));
+            }
+            Some(length) => {
+                request.body = vec![0; length as usize];
+                reader.read_exact(&mut request.body).map_err(|e| read_error(&e))?;
+            }
+            None if request.method == "POST" => return Err(error(411, "missing Content-Length")),
+            None => {}
+        }
+        Ok(request)
+    }
+
+    fn route(&self, request: &Request) -> Response {
+        let segments = request.path.trim_matches('/').split('/').collect::<Vec<_>>();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["days"]) => (200, self.days()),
+            ("POST", ["days", day, "parts", part]) => {
+                let (Ok(day), Ok(part)) = (day.parse::<u8>(), part.parse::<u8>()) else {
+                    return error(404, "no such day or part");
+                };
+                let solver = request.query.split('&')
+                    .find_map(|pair| pair.strip_prefix("solver="))
+                    .unwrap_or("built-in");
+                self.solve(day, part, solver, &request.body)
+            }
+            (_, ["days"] | ["days", _, "parts", _]) => error(405, "method not allowed"),
+            _ => error(404, "not found"),
+        }
+    }
+
+    /// Every day, with its parts and who solves them.
+    fn days(&self) -> Value {
+        let mut days: Vec<(u8, Vec<Value>)> = vec![];
+        for solution in &self.solutions {
+            let part = json!({ "part": solution.part, "solver": solution.name() });
+            match days.iter_mut().find(|(day, _)| *day == solution.day) {
+                Some((_, parts)) => parts.push(part),
+                None => days.push((solution.day, vec![part])),
+            }
+        }
+        days.sort_by_key(|(day, _)| *day);
+        Value::Array(days.into_iter().map(|(day, parts)| json!({ "day": day, "parts": parts })).collect())
+    }
+
+    fn solve(&self, day: u8, part: u8, solver: &str, input: &[u8]) -> Response {
+        let Some(solution) = self.solutions.iter()
+            .find(|solution| solution.day == day && solution.part == part && solution.name() == solver)
+        else {
+            return error(404, &format!("no {solver} solution for day {day} part {part}"));
+        };
+        if std::str::from_utf8(input).is_err() {
+            return error(400, "input is not UTF-8");
+        }
+        if solution.is_built_in() && self.abandoned.count() >= self.max_abandoned {
+            return error(503, "too many timed-out solutions are still running; try again later");
+        }
+
+        // Solutions read their input from a file
+        let input = match Scratch::write(input) {
+            Ok(input) => input,
+            Err(e) => return error(500, &format!("could not save the input: {e}")),
+        };
+        let outcome = execute::solve_tracked(solution.clone(), &input.0, self.timeouts.get(day), &self.abandoned);
+        let status = match outcome.status {
+            Status::Ok => 200,
+            Status::TimedOut(_) => 504,
+            _ => 500,
+        };
+        (status, outcome_json(&outcome))
+    }
+}
+
+/// A request, with the path and query of its target split apart.
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    body: Vec<u8>,
+}
+
+/// The stream of a request, which must arrive before the deadline however
+/// slowly its bytes trickle in.
+struct Deadline {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buffer)
+    }
+}
+
+/// A posted input saved to a file of its own, deleted when dropped.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn write(input: &[u8]) -> io::Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let name = format!("runner_serve_{}_{}.txt", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed));
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, input)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Reads a line of the request head, which must end before the head's limit.
+fn read_line(head: &mut io::Take<impl BufRead>, line: &mut String) -> Result<(), Response> {
+    match head.read_line(line) {
+        Ok(_) if line.ends_with('\n') => Ok(()),
+        Ok(_) if head.limit() == 0 => Err(error(431, "request head too large")),
+        Ok(_) => Err(error(400, "request ended early")),
+        Err(e) => Err(read_error(&e)),
+    }
+}
+
+fn read_error(e: &io::Error) -> Response {
+    match e.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => error(408, "request took too long to send"),
+        io::ErrorKind::UnexpectedEof => error(400, "request ended early"),
+        _ => error(400, &format!("could not read the request: {e}")),
+    }
+}
+
+fn error(status: u16, message: &str) -> Response {
+    (status, json!({ "error": message }))
+}
+
+/// An outcome as JSON, with the answer tagged by type so clients can tell a
+/// number from text that looks like one.
+fn outcome_json(outcome: &Outcome) -> Value {
+    let answer = outcome.answer.as_ref().map(|answer| match answer {
+        Answer::Integer(n) => json!({ "type": "integer", "value": n }),
+        Answer::Text(text) => json!({ "type": "text", "value": text }),
+        Answer::Grid(rows) => json!({ "type": "grid", "value": rows }),
+    });
+    json!({
+        "day": outcome.day,
+        "part": outcome.part,
+        "solver": outcome.solver.as_deref().unwrap_or("built-in"),
+        "status": outcome.status.to_string(),
+        "answer": answer,
+        "elapsed_ns": outcome.elapsed.as_nanos() as u64,
+    })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Content Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, thread};
+
+    use super::*;
+
+    /// Starts a service on a free local port, solving day 1 by summing the
+    /// input's numbers and day 2 by never finishing.
+    fn start(max_input: u64, read_timeout: Duration) -> SocketAddr {
+        start_with(max_input, read_timeout, 8)
+    }
+
+    fn start_with(max_input: u64, read_timeout: Duration, max_abandoned: usize) -> SocketAddr {
+        let solutions = vec![
+            Solution::new(1, 1, |path| {
+                let input = fs::read_to_string(path).unwrap();
+                Answer::Integer(input.lines().map(|line| line.parse::<i64>().unwrap()).sum())
+            }),
+            Solution::new(1, 2, |_| Answer::Grid(vec!["#.".into(), ".#".into()])),
+            Solution::new(2, 1, |_| loop {
+                thread::sleep(Duration::from_secs(1));
+            }),
+            Solution::new(3, 1, |_| panic!("not today")),
+        ];
+        let service = Service {
+            solutions,
+            timeouts: Timeouts::new(Some(Duration::from_millis(100))),
+            max_input,
+            read_timeout,
+            max_abandoned,
+            abandoned: Abandoned::default(),
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, service, 4));
+        address
+    }
+
+    /// Sends `request` as is and returns the response's status and body.
+    fn send(address: SocketAddr, request: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn post(address: SocketAddr, path: &str, input: &str) -> (u16, Value) {
+        send(address, &format!("POST {path} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{input}", input.len()))
+    }
+
+    #[test]
+    fn list_days() {
+        let address = start(1 << 10, Duration::from_secs(5));
+        let (status, days) = send(address, "GET /days HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert_eq!(status, 200);
+        assert_eq!(days[0], json!({
+            "day": 1,
+            "parts": [{ "part": 1, "solver": "built-in" }, { "part": 2, "solver": "built-in" }],
+        }));
+        assert_eq!(days.as_array().unwrap().len(), 3);
+
+        assert_eq!(send(address, "GET /nowhere HTTP/1.1\r\n\r\n").0, 404);
+        assert_eq!(send(address, "DELETE /days HTTP/1.1\r\n\r\n").0, 405);
+    }
+
+    #[test]
+    fn solve_posted_input() {
+        let address = start(1 << 10, Duration::from_secs(5));
+        let (status, outcome) = post(address, "/days/1/parts/1", "3\n4\n");
+        assert_eq!(status, 200, "{outcome}");
+        assert_eq!(outcome["answer"], json!({ "type": "integer", "value": 7 }));
+        assert_eq!(outcome["status"], "ok");
+        assert_eq!(outcome["solver"], "built-in");
+        assert!(outcome["elapsed_ns"].as_u64().unwrap() > 0);
+
+        let (_, outcome) = post(address, "/days/1/parts/2", "");
+        assert_eq!(outcome["answer"], json!({ "type": "grid", "value": ["#.", ".#"] }));
+
+        let (status, outcome) = post(address, "/days/3/parts/1", "");
+        assert_eq!(status, 500);
+        assert_eq!(outcome["status"], "panicked: not today");
+
+        assert_eq!(post(address, "/days/1/parts/1?solver=alice", "1").0, 404);
+        assert_eq!(post(address, "/days/9/parts/1", "1").0, 404);
+    }
+
+    #[test]
+    fn limits() {
+        let address = start(16, Duration::from_millis(200));
+
+        // Solutions are abandoned after their timeout
+        let (status, outcome) = post(address, "/days/2/parts/1", "1");
+        assert_eq!(status, 504);
+        assert_eq!(outcome["status"], "timed out after 100.00ms");
+
+        // Large inputs are refused without being read
+        let (status, body) = send(address, "POST /days/1/parts/1 HTTP/1.1\r\nContent-Length: 17\r\n\r\n");
+        assert_eq!(status, 413);
+        assert!(body["error"].as_str().unwrap().contains("the limit is 16"));
+        assert_eq!(send(address, "POST /days/1/parts/1 HTTP/1.1\r\n\r\n").0, 411);
+        let huge = format!("GET /days HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "x".repeat(MAX_HEAD as usize));
+        assert_eq!(send(address, &huge).0, 431);
+
+        // A request that never finishes arriving is cut off
+        let start = Instant::now();
+        let (status, _) = send(address, "POST /days/1/parts/1 HTTP/1.1\r\nContent-Length: 10\r\n\r\n12");
+        assert_eq!(status, 408);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn caps_abandoned_solutions() {
+        let address = start_with(1 << 10, Duration::from_secs(5), 3);
+        for _ in 0..3 {
+            assert_eq!(post(address, "/days/2/parts/1", "1").0, 504);
+        }
+
+        // Day 2 never finishes, so its threads pile up until the cap
+        let (status, body) = post(address, "/days/2/parts/1", "1");
+        assert_eq!(status, 503);
+        assert!(body["error"].as_str().unwrap().contains("too many timed-out solutions"));
+        assert_eq!(post(address, "/days/1/parts/1", "1\n2").0, 503);
+        assert_eq!(send(address, "GET /days HTTP/1.1\r\n\r\n").0, 200);
+    }
+}
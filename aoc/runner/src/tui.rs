@@ -0,0 +1,554 @@
+//! A terminal dashboard for the calendar: every day with its input, known
+//! answers and last run, a key to run a day or one part of it, and a view for
+//! each day's visualizations.
+
+use std::{
+    collections::BTreeMap,
+    io,
+    panic,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind},
+        terminal::{self, EnterAlternateScreen},
+    },
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Table, TableState},
+    DefaultTerminal, Frame, Terminal,
+};
+
+use crate::{
+    batch::{Expected, SIDECAR_EXTENSION},
+    execute::{self, Timeouts},
+    registry::{self, Solution, Visualization},
+    report::{self, Outcome},
+};
+
+/// How many past timings to show per part.
+const HISTORY_LENGTH: usize = 8;
+
+/// How long to wait for a key before checking on running solutions.
+const TICK: Duration = Duration::from_millis(100);
+
+/// One row of the calendar.
+struct Day {
+    day: u8,
+    solutions: Vec<Solution>,
+    visualizations: Vec<Visualization>,
+    input: PathBuf,
+    /// Answers from the sidecar beside the input, e.g. `day_1.expected`
+    expected: Expected,
+    /// The latest outcome of each part and solver
+    last: BTreeMap<(u8, Option<String>), Outcome>,
+    /// Every outcome this session, oldest first
+    history: Vec<Outcome>,
+}
+
+impl Day {
+    fn has_input(&self) -> bool {
+        self.input.is_file()
+    }
+
+    fn parts(&self) -> Vec<u8> {
+        let mut parts = self.solutions.iter().map(|solution| solution.part).collect::<Vec<_>>();
+        parts.sort_unstable();
+        parts.dedup();
+        parts
+    }
+
+    /// Whether `outcome` gave the answer in the sidecar, if there is one.
+    fn check(&self, outcome: &Outcome) -> Option<bool> {
+        self.expected.check(outcome.part, outcome.answer.as_ref()?)
+    }
+
+    /// A word or two on how the latest runs went.
+    fn last_result(&self) -> String {
+        if let Some(failed) = self.last.values().find(|outcome| !outcome.is_ok()) {
+            return failed.status.to_string();
+        }
+        let checks = self.last.values().map(|outcome| self.check(outcome)).collect::<Vec<_>>();
+        if checks.is_empty() {
+            "-".to_string()
+        } else if checks.contains(&Some(false)) {
+            "wrong answer".to_string()
+        } else if checks.iter().all(|check| *check == Some(true)) {
+            "correct".to_string()
+        } else {
+            "ok".to_string()
+        }
+    }
+}
+
+/// What the right-hand pane shows.
+enum View {
+    /// The selected day's latest answers
+    Output,
+    Visualization { name: &'static str, picture: String },
+}
+
+/// The dashboard's state. Drawing and key handling are separate from the
+/// terminal, so both can be tested against an in-memory one.
+pub struct App {
+    days: Vec<Day>,
+    table: TableState,
+    view: View,
+    timeouts: Timeouts,
+    /// Outcomes from runs in the background
+    results: (Sender<Outcome>, Receiver<Outcome>),
+    running: usize,
+    message: String,
+}
+
+impl App {
+    pub fn new(
+        solutions: Vec<Solution>,
+        visualizations: Vec<Visualization>,
+        inputs: PathBuf,
+        timeouts: Timeouts,
+    ) -> Self {
+        let mut numbers = solutions.iter().map(|solution| solution.day).collect::<Vec<_>>();
+        numbers.sort_unstable();
+        numbers.dedup();
+
+        let mut message = String::new();
+        let days = numbers.into_iter()
+            .map(|day| {
+                let input = registry::input_path(&inputs, day);
+                let expected = Expected::load(&input.with_extension(SIDECAR_EXTENSION))
+                    .unwrap_or_else(|e| {
+                        message = e;
+                        Expected::default()
+                    });
+                Day {
+                    day,
+                    solutions: solutions.iter().filter(|solution| solution.day == day).cloned().collect(),
+                    visualizations: visualizations.iter().filter(|v| v.day == day).copied().collect(),
+                    input,
+                    expected,
+                    last: BTreeMap::new(),
+                    history: vec![],
+                }
+            })
+            .collect();
+
+        Self {
+            days,
+            table: TableState::default().with_selected(0),
+            view: View::Output,
+            timeouts,
+            results: mpsc::channel(),
+            running: 0,
+            message,
+        }
+    }
+
+    fn selected(&self) -> Option<&Day> {
+        self.days.get(self.table.selected()?)
+    }
+
+    /// Acts on a key press. Returns `false` once the user asks to quit.
+    pub fn handle(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') => return false,
+            KeyCode::Esc => match self.view {
+                View::Output => return false,
+                View::Visualization { .. } => self.view = View::Output,
+            },
+            KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select(1),
+            KeyCode::Enter | KeyCode::Char('r') => self.run(None),
+            KeyCode::Char(c @ '1'..='9') => self.run(c.to_digit(10).map(|part| part as u8)),
+            KeyCode::Char('v') => self.visualize(),
+            KeyCode::Char('o') => self.view = View::Output,
+            _ => {}
+        }
+        true
+    }
+
+    fn select(&mut self, step: isize) {
+        let Some(selected) = self.table.selected() else { return };
+        let selected = selected.saturating_add_signed(step).min(self.days.len().saturating_sub(1));
+        self.table.select(Some(selected));
+        self.view = View::Output;
+    }
+
+    /// Runs the selected day's solutions for `part`, or every part, in the
+    /// background.
+    fn run(&mut self, part: Option<u8>) {
+        let Some(day) = self.selected() else { return };
+        let solutions = day.solutions.iter()
+            .filter(|solution| part.is_none_or(|part| solution.part == part))
+            .cloned()
+            .collect::<Vec<_>>();
+        if solutions.is_empty() {
+            self.message = format!("Day {} has no part {}", day.day, part.unwrap_or_default());
+            return;
+        }
+
+        let input = day.input.clone();
+        let timeout = self.timeouts.get(day.day);
+        self.message = format!("Running day {}", day.day);
+        let tx = self.results.0.clone();
+        self.running += solutions.len();
+        self.view = View::Output;
+        // A panic's message would be drawn over the dashboard, and shows in
+        // the outcome anyway
+        thread::spawn(move || execute::quietly(|| {
+            for solution in solutions {
+                let _ = tx.send(execute::solve(solution, &input, timeout));
+            }
+        }));
+    }
+
+    /// Shows the selected day's next visualization.
+    fn visualize(&mut self) {
+        let Some(day) = self.selected() else { return };
+        if day.visualizations.is_empty() {
+            self.message = format!("Day {} has no visualizations", day.day);
+            return;
+        }
+        if !day.has_input() {
+            self.message = format!("Day {} has no input to draw", day.day);
+            return;
+        }
+
+        // Pressing `v` again moves on to the day's next one
+        let next = match self.view {
+            View::Visualization { name, .. } => day.visualizations.iter()
+                .position(|v| v.name == name)
+                .map_or(0, |i| (i + 1) % day.visualizations.len()),
+            View::Output => 0,
+        };
+        let visualization = day.visualizations[next];
+        let path = day.input.to_string_lossy().to_string();
+        let picture = execute::quietly(|| panic::catch_unwind(|| (visualization.render)(&path)));
+
+        match picture {
+            Ok(picture) => self.view = View::Visualization { name: visualization.name, picture },
+            Err(payload) => {
+                self.message = format!("{} panicked: {}", visualization.name, execute::panic_message(payload));
+            }
+        }
+    }
+
+    /// Takes in the outcomes of any runs that have finished.
+    pub fn update(&mut self) {
+        while let Ok(outcome) = self.results.1.try_recv() {
+            self.record(outcome);
+        }
+    }
+
+    fn record(&mut self, outcome: Outcome) {
+        self.running -= 1;
+        if self.running == 0 {
+            self.message.clear();
+        }
+        if let Some(day) = self.days.iter_mut().find(|day| day.day == outcome.day) {
+            day.last.insert((outcome.part, outcome.solver.clone()), outcome.clone());
+            day.history.push(outcome);
+        }
+    }
+
+    /// Waits for every run in the background to finish.
+    #[cfg(test)]
+    fn wait(&mut self) {
+        while self.running > 0 {
+            let outcome = self.results.1.recv().unwrap();
+            self.record(outcome);
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [calendar, right] = Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .areas(main);
+        let [output, history] = Layout::vertical([Constraint::Min(0), Constraint::Length(HISTORY_LENGTH as u16)])
+            .areas(right);
+
+        let rows = self.days.iter()
+            .map(|day| {
+                let known = day.parts().iter().filter(|&&part| day.expected.get(part).is_some()).count();
+                let elapsed = day.last.values().map(|outcome| outcome.elapsed).sum::<Duration>();
+                Row::new([
+                    format!("{:>3}", day.day),
+                    if day.has_input() { "yes" } else { "no" }.to_string(),
+                    format!("{known}/{}", day.parts().len()),
+                    day.last_result(),
+                    if day.last.is_empty() { "-".to_string() } else { format!("{elapsed:.2?}") },
+                ])
+            })
+            .collect::<Vec<_>>();
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Length(6),
+            Constraint::Min(10),
+            Constraint::Length(10),
+        ];
+        let table = Table::new(rows, widths)
+            .header(Row::new(["Day", "Input", "Known", "Last run", "Time"]).style(Style::new().add_modifier(Modifier::BOLD)))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .block(Block::bordered().title("Calendar"));
+        frame.render_stateful_widget(table, calendar, &mut self.table);
+
+        let Some(day) = self.selected() else { return };
+        let pane = match &self.view {
+            View::Output => Paragraph::new(self.output_lines(day))
+                .block(Block::bordered().title(format!("Day {}", day.day))),
+            View::Visualization { name, picture } => Paragraph::new(picture.as_str())
+                .block(Block::bordered().title(format!("Day {} {name}", day.day))),
+        };
+        frame.render_widget(pane, output);
+
+        let timings = self.history_lines(day);
+        frame.render_widget(Paragraph::new(timings).block(Block::bordered().title("History")), history);
+
+        let help = "↑↓ select  enter run day  1-9 run part  v visualize  o output  q quit";
+        let status = if self.message.is_empty() { help.to_string() } else { self.message.clone() };
+        frame.render_widget(Paragraph::new(status), footer);
+    }
+
+    /// The latest answer of each part, with how it compares to the sidecar.
+    fn output_lines(&self, day: &Day) -> Vec<Line<'static>> {
+        if day.last.is_empty() {
+            return vec![Line::from("Not run yet. Press enter to run every part.")];
+        }
+
+        let mut lines = vec![];
+        for outcome in day.last.values() {
+            let verdict = match day.check(outcome) {
+                Some(true) => ", correct",
+                Some(false) => ", wrong answer",
+                None => "",
+            };
+            lines.push(Line::from(format!(
+                "Part {} ({}) in {:.2?}: {}{verdict}",
+                outcome.part,
+                report::solver_cell(outcome.solver.as_deref()),
+                outcome.elapsed,
+                outcome.status,
+            )));
+            for answer in report::answer_cell(outcome.answer.as_ref()).lines() {
+                lines.push(Line::from(format!("  {answer}")));
+            }
+        }
+        lines
+    }
+
+    /// Recent timings of each part, newest last.
+    fn history_lines(&self, day: &Day) -> Vec<Line<'static>> {
+        day.last.keys()
+            .map(|(part, solver)| {
+                let runs = day.history.iter()
+                    .filter(|outcome| outcome.part == *part && outcome.solver == *solver)
+                    .collect::<Vec<_>>();
+                let recent = runs[runs.len().saturating_sub(HISTORY_LENGTH)..].iter()
+                    .map(|outcome| format!("{:.2?}", outcome.elapsed))
+                    .collect::<Vec<_>>();
+                Line::from(format!(
+                    "Part {part} ({}): {}",
+                    report::solver_cell(solver.as_deref()),
+                    recent.join("  "),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Runs the dashboard in the terminal until the user quits.
+pub fn run(mut app: App) -> io::Result<()> {
+    // Log events would be drawn over the dashboard too; they are printed once
+    // it closes
+    let _held = aoc::logging::hold();
+    let mut terminal = init()?;
+    let result = event_loop(&mut app, &mut terminal);
+    ratatui::restore();
+    result
+}
+
+/// Sets the terminal up like `ratatui::init`, but with a panic hook that
+/// restores it only for panics that are not caught and reported: `ratatui`'s
+/// own would do so whenever a solution panicked, under a dashboard still
+/// being drawn.
+fn init() -> io::Result<DefaultTerminal> {
+    execute::install_panic_hook();
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !execute::is_quiet() {
+            ratatui::restore();
+        }
+        hook(info);
+    }));
+
+    terminal::enable_raw_mode()?;
+    ratatui::crossterm::execute!(io::stdout(), EnterAlternateScreen)?;
+    Terminal::new(CrosstermBackend::new(io::stdout()))
+}
+
+fn event_loop<B: Backend>(app: &mut App, terminal: &mut Terminal<B>) -> io::Result<()> {
+    loop {
+        app.update();
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !app.handle(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Arc};
+
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+    use crate::{external::External, registry::Solver, sandbox::Limits};
+
+    fn app(test: &str) -> App {
+        let inputs = std::env::temp_dir().join("runner_tui").join(test);
+        let _ = fs::remove_dir_all(&inputs);
+        fs::create_dir_all(&inputs).unwrap();
+        fs::write(inputs.join("day_1.txt"), "21").unwrap();
+        fs::write(inputs.join("day_1.expected"), "1: 21\n2: 43").unwrap();
+
+        let solutions = vec![
+            Solution::new(1, 1, |path| fs::read_to_string(path).unwrap().into()),
+            Solution::new(1, 2, |path| {
+                let n = fs::read_to_string(path).unwrap().parse::<u32>().unwrap();
+                (n * 2).into()
+            }),
+            Solution::new(2, 1, |_| "never run".into()),
+        ];
+        let visualizations = vec![
+            Visualization { day: 1, name: "tally", render: |path| "|".repeat(fs::read_to_string(path).unwrap().len()) },
+            Visualization { day: 1, name: "broken", render: |_| panic!("no pictures today") },
+        ];
+        App::new(solutions, visualizations, inputs, Timeouts::default())
+    }
+
+    /// The screen as text, one line per row.
+    fn render(app: &mut App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 16)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn calendar() {
+        let mut app = app("calendar");
+        let screen = render(&mut app);
+        assert!(screen.contains("│Day Input Known  Last run"), "{screen}");
+        assert!(screen.contains("│  1 yes   2/2    -"), "{screen}");
+        assert!(screen.contains("│  2 no    0/1    -"), "{screen}");
+        assert!(screen.contains("Not run yet"), "{screen}");
+    }
+
+    #[test]
+    fn external_solvers_share_parts() {
+        let inputs = std::env::temp_dir().join("runner_tui").join("external_solvers_share_parts");
+        let _ = fs::remove_dir_all(&inputs);
+        fs::create_dir_all(&inputs).unwrap();
+        fs::write(inputs.join("day_1.expected"), "1: 21\n2: 43").unwrap();
+
+        // Registered after the built-in parts, as `external::load` does
+        let alice = Arc::new(External {
+            name: "alice".to_string(),
+            program: "alice".to_string(),
+            args: vec![],
+            dir: inputs.clone(),
+            limits: Limits::NONE,
+        });
+        let solutions = vec![
+            Solution::new(1, 1, |_| "21".into()),
+            Solution::new(1, 2, |_| "43".into()),
+            Solution { day: 1, part: 1, solver: Solver::External(Arc::clone(&alice)) },
+            Solution { day: 1, part: 2, solver: Solver::External(alice) },
+        ];
+        let mut app = App::new(solutions, vec![], inputs, Timeouts::default());
+        assert_eq!(app.days[0].parts(), [1, 2]);
+        let screen = render(&mut app);
+        assert!(screen.contains("│  1 no    2/2    -"), "{screen}");
+    }
+
+    #[test]
+    fn run_day_and_part() {
+        let mut app = app("run_day_and_part");
+        assert!(app.handle(KeyCode::Enter));
+        app.wait();
+        let screen = render(&mut app);
+        assert!(screen.contains("│  1 yes   2/2    wrong answer"), "{screen}");
+        assert!(screen.contains("Part 1 (built-in) in"), "{screen}");
+        assert!(screen.contains("ok, correct"), "{screen}");
+        assert!(screen.contains("ok, wrong answer"), "{screen}");
+        assert!(screen.contains("│  42 "), "{screen}");
+
+        assert!(app.handle(KeyCode::Char('1')));
+        app.wait();
+        assert_eq!(app.days[0].history.len(), 3);
+        let screen = render(&mut app);
+        let history = screen.lines().find(|line| line.contains("│Part 1 (built-in): ")).unwrap();
+        let timings = history.split(": ").nth(1).unwrap().trim_end_matches([' ', '│']);
+        assert_eq!(timings.split_whitespace().count(), 2, "{history}");
+
+        // Day 2 has no input
+        assert!(app.handle(KeyCode::Down));
+        assert!(app.handle(KeyCode::Enter));
+        app.wait();
+        assert!(render(&mut app).contains("│  2 no    0/1    missing input"));
+    }
+
+    #[test]
+    fn runs_quietly() {
+        let inputs = std::env::temp_dir().join("runner_tui").join("runs_quietly");
+        fs::create_dir_all(&inputs).unwrap();
+        fs::write(inputs.join("day_3.txt"), "").unwrap();
+        let solutions = vec![Solution::new(3, 1, |_| execute::is_quiet().to_string().into())];
+        let visualizations = vec![Visualization { day: 3, name: "quiet", render: |_| execute::is_quiet().to_string() }];
+        let mut app = App::new(solutions, visualizations, inputs, Timeouts::default());
+
+        assert!(app.handle(KeyCode::Enter));
+        app.wait();
+        assert_eq!(app.days[0].history[0].answer, Some("true".into()));
+        assert!(app.handle(KeyCode::Char('v')));
+        assert!(render(&mut app).contains("│true"));
+    }
+
+    #[test]
+    fn visualizations() {
+        let mut app = app("visualizations");
+        assert!(app.handle(KeyCode::Char('v')));
+        let screen = render(&mut app);
+        assert!(screen.contains("Day 1 tally"), "{screen}");
+        assert!(screen.contains("│||"), "{screen}");
+
+        // On to the next, which panics
+        assert!(app.handle(KeyCode::Char('v')));
+        assert!(render(&mut app).contains("broken panicked: no pictures today"));
+
+        // Escape closes the picture, then quits
+        assert!(app.handle(KeyCode::Esc));
+        assert!(render(&mut app).contains("Not run yet"));
+        assert!(!app.handle(KeyCode::Esc));
+
+        assert!(app.handle(KeyCode::Down));
+        assert!(app.handle(KeyCode::Char('v')));
+        assert!(render(&mut app).contains("Day 2 has no visualizations"));
+    }
+}
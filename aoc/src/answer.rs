@@ -0,0 +1,183 @@
+use std::fmt::Display;
+
+/// The answer to one part of a puzzle. Most answers are numbers, but some
+/// puzzles ask for a string (day 5's top crates) or draw their answer as a
+/// picture (day 10's CRT).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    /// Rows of a rendered screen, where `#` is a lit pixel.
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// Turns a `Grid` answer into `Text` using `ocr`. Other answers, and grids
+    /// that `ocr` cannot read, are returned unchanged.
+    pub fn read_with<F>(self, ocr: F) -> Self
+    where
+        F: Fn(&[String]) -> Option<String>,
+    {
+        match self {
+            Self::Grid(rows) => match ocr(&rows) {
+                Some(text) => Self::Text(text),
+                None => Self::Grid(rows),
+            },
+            other => other,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(n) => write!(f, "{n}"),
+            Self::Text(s) => write!(f, "{s}"),
+            Self::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Self::Integer(i64::from(n))
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, u8, u16, u32);
+
+// These can overflow an i64 in theory, but no puzzle answer gets that large.
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Self::Integer(i64::try_from(n).expect("Answer should fit in an i64"))
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Self::Integer(i64::try_from(n).expect("Answer should fit in an i64"))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(rows: Vec<String>) -> Self {
+        Self::Grid(rows)
+    }
+}
+
+/// Capital letters as drawn by the puzzles' 4x6 pixel font. Letters are
+/// separated by one blank column, so each takes up five columns of the screen.
+const FONT: [(char, [&str; 6]); 16] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads capital letters drawn in the puzzles' 4x6 pixel font, such as the
+/// screen rendered by day 10. Any pixel other than `#` counts as dark. Returns
+/// `None` if the grid is not six rows tall or holds an unknown letter.
+///
+/// Pass this to [`Answer::read_with`] to turn a grid answer into text.
+pub fn ocr(rows: &[String]) -> Option<String> {
+    if rows.len() != 6 {
+        return None;
+    }
+
+    let rows = rows.iter()
+        .map(|row| row.chars().map(|c| c == '#').collect::<Vec<bool>>())
+        .collect::<Vec<_>>();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let pixel = |row: usize, column: usize| *rows[row].get(column).unwrap_or(&false);
+
+    let mut text = String::new();
+    for start in (0..width).step_by(5) {
+        let letter = FONT.iter().find(|(_, glyph)| {
+            glyph.iter().enumerate().all(|(row, pattern)| {
+                pattern.chars().enumerate()
+                    .all(|(column, c)| pixel(row, start + column) == (c == '#'))
+            })
+        })?;
+        text.push(letter.0);
+    }
+
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Vec<String> {
+        rows.iter().map(|row| row.to_string()).collect()
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Answer::from(24000_u32).to_string(), "24000");
+        assert_eq!(Answer::from(-3_i32).to_string(), "-3");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+        assert_eq!(Answer::from(grid(&["#.", ".#"])).to_string(), "#.\n.#");
+    }
+
+    #[test]
+    fn equality() {
+        assert_eq!(Answer::from(7_usize), Answer::from(7_u8));
+        assert_ne!(Answer::from(7_usize), Answer::from("7"));
+    }
+
+    #[test]
+    fn read_grid() {
+        let screen = grid(&[
+            "#..#.####.#....###..",
+            "#..#.#....#....#..#.",
+            "####.###..#....#..#.",
+            "#..#.#....#....###..",
+            "#..#.#....#....#....",
+            "#..#.####.####.#....",
+        ]);
+        let answer = Answer::from(screen).read_with(ocr);
+        assert_eq!(answer, Answer::from("HELP"));
+    }
+
+    #[test]
+    fn unreadable_grid_is_unchanged() {
+        let screen = grid(&["##..##..", "##..##..", "", "", "", ""]);
+        let answer = Answer::from(screen.clone()).read_with(ocr);
+        assert_eq!(answer, Answer::Grid(screen));
+
+        // Only grids are run through OCR
+        assert_eq!(Answer::from(5_u32).read_with(ocr), Answer::from(5_u32));
+    }
+}
@@ -0,0 +1,74 @@
+//! Prints a private leaderboard: standings, and each member's times per day.
+//!
+//! ```text
+//! $ cargo run --features leaderboard --bin leaderboard -- --id 123456 --markdown
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+use aoc::{fetch::Fetcher, leaderboard::Leaderboard};
+use clap::Parser;
+
+/// The site asks that private leaderboards are fetched at most every 15 minutes.
+const MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Parser)]
+#[command(about = "Prints a private leaderboard: standings, and each member's times per day.")]
+struct Cli {
+    /// Read the leaderboard JSON from this file instead of downloading it.
+    #[arg(long, conflicts_with = "id")]
+    file: Option<PathBuf>,
+    /// Private leaderboard ID to download. Needs `AOC_SESSION`.
+    #[arg(long, required_unless_present = "file")]
+    id: Option<u64>,
+    /// Event year.
+    #[arg(long, default_value_t = 2022)]
+    year: u16,
+    /// Print Markdown tables instead, e.g. for the team wiki.
+    #[arg(long)]
+    markdown: bool,
+    /// Write to this file instead of stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let Cli { file, id, year, markdown, output } = Cli::parse();
+    let _ = aoc::logging::init(0, None);
+
+    let json = match (file, id) {
+        (Some(file), _) => std::fs::read_to_string(&file)
+            .map_err(|e| format!("Could not read {}: {e}", file.display())),
+        // The same cache as the runner's
+        (None, Some(id)) => Fetcher::new(Path::new(env!("CARGO_MANIFEST_DIR")).join(".cache"))
+            .get_fresh_within(&format!("/{year}/leaderboard/private/view/{id}.json"), MAX_AGE)
+            .map_err(|e| format!("Could not download leaderboard: {e}")),
+        (None, None) => unreachable!("clap requires --file or --id"),
+    };
+    let leaderboard = json.and_then(|json| {
+        Leaderboard::from_json(&json).map_err(|e| format!("Invalid leaderboard: {e}"))
+    });
+    let leaderboard = match leaderboard {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let rendered = if markdown { leaderboard.to_markdown() } else { leaderboard.to_text() };
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, rendered) {
+                eprintln!("Could not write {}: {e}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{rendered}"),
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,519 @@
+//! Collections for the small sets and tallies the puzzles are full of: sets
+//! of small integers as bits, a multiset that counts what is put in it, and
+//! hash maps and sets with a fast hasher.
+//!
+//! The standard library hashes with SipHash, which resists deliberately
+//! colliding keys at some cost in speed. Puzzle inputs do not attack us, so
+//! [`FastHashMap`] and [`FastHashSet`] use [`FastHasher`] instead, which is
+//! several times quicker on small keys like coordinates.
+//!
+//! ```
+//! use aoc::collections::{Counter, FastHashSet, FixedBitSet};
+//!
+//! let letters = "abcab".chars().map(|c| c as usize).collect::<FixedBitSet<2>>();
+//! assert_eq!(letters.len(), 3);
+//!
+//! let counts = "abcab".chars().collect::<Counter<char>>();
+//! assert_eq!(counts.get(&'a'), 2);
+//! assert_eq!(counts.most_common()[0], (&'a', 2));
+//!
+//! let mut visited = FastHashSet::default();
+//! visited.insert((0_isize, 0_isize));
+//! assert!(visited.contains(&(0, 0)));
+//! ```
+
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hash, Hasher},
+};
+
+const WORD: usize = u64::BITS as usize;
+
+/// A set of small integers, one bit each, that grows to fit the largest.
+#[derive(Debug, Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty set with room for `0..bits` without growing.
+    pub fn with_capacity(bits: usize) -> Self {
+        Self { words: Vec::with_capacity(bits.div_ceil(WORD)), len: 0 }
+    }
+
+    /// Adds `i`, returning whether it was new.
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (word, bit) = (i / WORD, 1 << (i % WORD));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        self.len += usize::from(new);
+        new
+    }
+
+    /// Removes `i`, returning whether it was there.
+    pub fn remove(&mut self, i: usize) -> bool {
+        let Some(word) = self.words.get_mut(i / WORD) else { return false };
+        let bit = 1 << (i % WORD);
+        let present = *word & bit != 0;
+        *word &= !bit;
+        self.len -= usize::from(present);
+        present
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        self.words.get(i / WORD).is_some_and(|word| word & (1 << (i % WORD)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// The members, smallest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        ones(&self.words)
+    }
+
+    /// Adds every member of `other`.
+    pub fn union_with(&mut self, other: &Self) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+        self.len = count(&self.words);
+    }
+
+    /// Removes every member not also in `other`.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.words.truncate(other.words.len());
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+        self.len = count(&self.words);
+    }
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        // Words past the last member are zero, however many there are
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        long[..short.len()] == short[..] && long[short.len()..].iter().all(|&word| word == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for i in iter {
+            self.insert(i);
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// A set of the integers below `64 * WORDS`, one bit each, that lives on
+/// the stack. For sets of letters, say, that are made and thrown away often.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedBitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> FixedBitSet<WORDS> {
+    /// The integers the set can hold are those below this.
+    pub const CAPACITY: usize = WORDS * WORD;
+
+    pub const fn new() -> Self {
+        Self { words: [0; WORDS] }
+    }
+
+    /// Adds `i`, returning whether it was new.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below [`Self::CAPACITY`].
+    pub fn insert(&mut self, i: usize) -> bool {
+        assert!(i < Self::CAPACITY, "{i} does not fit in a set of the integers below {}", Self::CAPACITY);
+        let bit = 1 << (i % WORD);
+        let new = self.words[i / WORD] & bit == 0;
+        self.words[i / WORD] |= bit;
+        new
+    }
+
+    /// Removes `i`, returning whether it was there.
+    pub fn remove(&mut self, i: usize) -> bool {
+        let present = self.contains(i);
+        if present {
+            self.words[i / WORD] &= !(1 << (i % WORD));
+        }
+        present
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        i < Self::CAPACITY && self.words[i / WORD] & (1 << (i % WORD)) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        count(&self.words)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words = [0; WORDS];
+    }
+
+    /// The members, smallest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        ones(&self.words)
+    }
+
+    /// The members of either set.
+    pub fn union(&self, other: &Self) -> Self {
+        Self { words: std::array::from_fn(|i| self.words[i] | other.words[i]) }
+    }
+
+    /// The members of both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self { words: std::array::from_fn(|i| self.words[i] & other.words[i]) }
+    }
+}
+
+impl<const WORDS: usize> Default for FixedBitSet<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WORDS: usize> Extend<usize> for FixedBitSet<WORDS> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for i in iter {
+            self.insert(i);
+        }
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for FixedBitSet<WORDS> {
+    /// # Panics
+    ///
+    /// If an integer is not below [`Self::CAPACITY`].
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+fn count(words: &[u64]) -> usize {
+    words.iter().map(|word| word.count_ones() as usize).sum()
+}
+
+/// The position of every set bit in `words`, lowest first.
+fn ones(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    words.iter().enumerate().flat_map(|(i, &word)| {
+        let mut rest = word;
+        std::iter::from_fn(move || {
+            (rest != 0).then(|| {
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                i * WORD + bit
+            })
+        })
+    })
+}
+
+/// A multiset: how many times each value was added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: FastHashMap<T, usize>,
+    total: usize,
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Self { counts: FastHashMap::default(), total: 0 }
+    }
+
+    /// Adds one `item`, returning how many there are now.
+    pub fn add(&mut self, item: T) -> usize {
+        self.add_n(item, 1)
+    }
+
+    /// Adds `n` of `item`, returning how many there are now.
+    pub fn add_n(&mut self, item: T, n: usize) -> usize {
+        // Values are only kept while there are some
+        if n == 0 {
+            return self.get(&item);
+        }
+        self.total += n;
+        let count = self.counts.entry(item).or_default();
+        *count += n;
+        *count
+    }
+
+    /// Takes away one `item`, returning whether there was one.
+    pub fn remove<Q>(&mut self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let Some(count) = self.counts.get_mut(item) else { return false };
+        *count -= 1;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+        self.total -= 1;
+        true
+    }
+
+    /// How many of `item` there are.
+    pub fn get<Q>(&self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// Number of distinct values.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Number of values, counting each as many times as it was added.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Each distinct value and how many of it there are, in no order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// Each distinct value and how many of it there are, most first.
+    pub fn most_common(&self) -> Vec<(&T, usize)> {
+        let mut counts = self.iter().collect::<Vec<_>>();
+        counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        counts
+    }
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+/// A fast hasher for small keys, after the one rustc uses (FxHash). Not for
+/// keys an adversary picks: it is easy to make collide.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastHasher {
+    hash: u64,
+}
+
+impl FastHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+impl Hasher for FastHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i.into());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(i.into());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i.into());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Builds [`FastHasher`]s, for `HashMap::with_hasher` and the like.
+pub type FastBuildHasher = BuildHasherDefault<FastHasher>;
+
+/// A `HashMap` with [`FastHasher`]. Make one with `FastHashMap::default()`.
+pub type FastHashMap<K, V> = HashMap<K, V, FastBuildHasher>;
+
+/// A `HashSet` with [`FastHasher`]. Make one with `FastHashSet::default()`.
+pub type FastHashSet<T> = HashSet<T, FastBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn bit_sets() {
+        let mut set = BitSet::with_capacity(10);
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(200));
+        assert_eq!((set.len(), set.contains(200), set.contains(1000)), (2, true, false));
+        assert!(set.remove(200));
+        assert!(!set.remove(1000));
+        // Equal despite the room left over from 200
+        assert_eq!(set, BitSet::from_iter([3]));
+
+        let mut other = BitSet::from_iter([1, 3, 64, 130]);
+        other.intersect_with(&BitSet::from_iter([3, 130, 131]));
+        assert_eq!(other.iter().collect::<Vec<_>>(), [3, 130]);
+        other.union_with(&BitSet::from_iter([0, 500]));
+        assert_eq!((other.len(), other.iter().last()), (4, Some(500)));
+
+        let letters = |word: &str| word.bytes().map(usize::from).collect::<FixedBitSet<2>>();
+        assert_eq!(letters("vJrwpWtwJgWr").intersection(&letters("hcsFMMfFFhFp")).iter().collect::<Vec<_>>(), [b'p' as usize]);
+        assert_eq!(letters("abc").union(&letters("cd")).len(), 4);
+        assert!(!letters("a").contains(300));
+        assert!(FixedBitSet::<1>::new().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "128 does not fit")]
+    fn fixed_bit_sets_are_fixed() {
+        FixedBitSet::<2>::new().insert(128);
+    }
+
+    #[test]
+    fn counters() {
+        let mut counter = "mississippi".chars().collect::<Counter<_>>();
+        assert_eq!((counter.len(), counter.total()), (4, 11));
+        // 'i' and 's' tie, in either order
+        assert_eq!(counter.most_common().iter().map(|&(_, count)| count).collect::<Vec<_>>(), [4, 4, 2, 1]);
+        assert_eq!(counter.most_common().last(), Some(&(&'m', 1)));
+        assert!(counter.remove(&'m'));
+        assert!(!counter.remove(&'m'));
+        assert_eq!((counter.get(&'m'), counter.len(), counter.total()), (0, 3, 10));
+        assert_eq!(counter.add_n('p', 3), 5);
+        assert_eq!(counter.add_n('z', 0), 0);
+        assert_eq!((counter.len(), counter.total()), (3, 13));
+        assert_eq!(counter, "ississippippp".chars().collect());
+
+        let words = ["a", "b", "a"].map(String::from).into_iter().collect::<Counter<String>>();
+        assert_eq!(words.get("a"), 2);
+    }
+
+    #[test]
+    fn fast_hasher() {
+        let hash = |value: &(isize, isize)| {
+            let mut hasher = FastHasher::default();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+        // The same every run, unlike SipHash with its random keys
+        assert_eq!(hash(&(1, 2)), hash(&(1, 2)));
+        assert_ne!(hash(&(1, 2)), hash(&(2, 1)));
+
+        let mut hasher = FastHasher::default();
+        hasher.write(b"nine byte");
+        assert_ne!(hasher.finish(), FastHasher::default().finish());
+
+        let mut map = FastHashMap::default();
+        *map.entry("rope").or_insert(0) += 1;
+        assert_eq!(map["rope"], 1);
+    }
+
+    proptest! {
+        #[test]
+        fn bit_set_is_a_set(ops in prop::collection::vec((any::<bool>(), 0_usize..300), 0..100)) {
+            let (mut bits, mut fixed, mut expected) = (BitSet::new(), FixedBitSet::<5>::new(), BTreeSet::new());
+            for (insert, i) in ops {
+                if insert {
+                    prop_assert_eq!(bits.insert(i), expected.insert(i));
+                    fixed.insert(i);
+                } else {
+                    prop_assert_eq!(bits.remove(i), expected.remove(&i));
+                    fixed.remove(i);
+                }
+            }
+            prop_assert_eq!(bits.len(), expected.len());
+            prop_assert_eq!(fixed.len(), expected.len());
+            prop_assert!(bits.iter().eq(expected.iter().copied()));
+            prop_assert!(fixed.iter().eq(expected.iter().copied()));
+        }
+    }
+}
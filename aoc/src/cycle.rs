@@ -0,0 +1,268 @@
+//! Finding where a simulation starts repeating, so it can be skipped ahead
+//! to a step far beyond any that could be run one at a time.
+//!
+//! A simulation here is a start state and a `step` function from each state
+//! to the next. Two states count as the same when their `key`s are equal, so
+//! a state can carry things (a step counter, a running total) that don't
+//! affect what happens next. Once a key comes round again everything after it
+//! repeats, and that repetition is a [`Cycle`].
+//!
+//! There are two ways to find it:
+//!
+//! - [`brent`] keeps only two states at a time, but to learn the state at a
+//!   far step it has to replay the simulation up to the matching step in the
+//!   first cycle ([`Cycle::replay`]).
+//! - [`find`] remembers every state and hashes every key, and answers from
+//!   those without stepping again ([`History`]).
+//!
+//! Both give up, returning `None`, once they have taken `limit` steps
+//! without finding the cycle. For [`find`] that is `start + length` steps,
+//! for [`brent`] it can be up to twice as many.
+//!
+//! ```
+//! use aoc::cycle;
+//!
+//! // 3, 10, 101, 2, 5, 26, 167, 95, 101, 2, ... with step 2 (101) coming
+//! // round again every 6 steps
+//! let step = |x: &u64| (x * x + 1) % 255;
+//! let cycle = cycle::brent(3, step, |&x| x, 1000).unwrap();
+//! assert_eq!((cycle.start, cycle.length), (2, 6));
+//! assert_eq!(cycle.replay(1_000_000_000, 3, step), 5);
+//!
+//! // Or the same with every state remembered, adding the states up as well
+//! let history = cycle::find(3, step, |&x| x, 1000).unwrap();
+//! assert_eq!(*history.state_at(1_000_000_001), 26);
+//! assert_eq!(history.total(6, |&x| x), Some(3 + 10 + 101 + 2 + 5 + 26));
+//! ```
+
+use std::hash::Hash;
+
+use crate::{collections::FastHashMap, math::Integer};
+
+/// Where a simulation starts repeating: the state after `start + length`
+/// steps is the same as after `start`, and no earlier pair of steps matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Steps before the first state that repeats
+    pub start: usize,
+    /// Steps between one time that state comes round and the next
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step whose state is the same as step `n`'s. Steps before
+    /// the cycle are their own; the rest map into the first time round it.
+    pub fn index(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// The state after `n` steps, found by running at most `start + length`
+    /// of them from `initial`.
+    pub fn replay<S>(&self, n: usize, initial: S, mut step: impl FnMut(&S) -> S) -> S {
+        (0..self.index(n)).fold(initial, |state, _| step(&state))
+    }
+
+    /// The total of `metric` over the states before step `n`, counting the
+    /// state at step 0 but not at step `n`, found by running at most
+    /// `start + length` steps from `initial`. `None` if it overflows `T`.
+    pub fn total<S, T>(
+        &self,
+        n: usize,
+        initial: S,
+        mut step: impl FnMut(&S) -> S,
+        mut metric: impl FnMut(&S) -> T,
+    ) -> Option<T>
+    where
+        T: Integer + TryFrom<usize>,
+    {
+        let steps = n.min(self.start + self.length);
+        let mut values = Vec::with_capacity(steps);
+        let mut state = initial;
+        for i in 0..steps {
+            values.push(metric(&state));
+            if i + 1 < steps {
+                state = step(&state);
+            }
+        }
+        total(self, n, &values)
+    }
+}
+
+/// Finds the cycle with Brent's algorithm, holding two states at a time.
+/// `None` if it has not found it after `limit` steps, which can take up to
+/// twice `start + length`.
+pub fn brent<S, K>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+    limit: usize,
+) -> Option<Cycle>
+where
+    S: Clone,
+    K: Eq,
+{
+    // Look for the length first: the tortoise waits at steps 1, 2, 4, 8, ...
+    // while the hare runs up to twice as far ahead of it, until it meets it
+    let mut power = 1;
+    let mut length = 1;
+    let mut steps = 1;
+    let mut tortoise = key(&initial);
+    let mut hare = step(&initial);
+    loop {
+        let hare_key = key(&hare);
+        if hare_key == tortoise {
+            break;
+        }
+        if steps >= limit {
+            return None;
+        }
+        if power == length {
+            tortoise = hare_key;
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+        steps += 1;
+    }
+
+    // Then the start: two states `length` steps apart meet first where the
+    // cycle begins
+    let mut tortoise = initial.clone();
+    let mut hare = (0..length).fold(initial, |state, _| step(&state));
+    let mut start = 0;
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+/// Finds the cycle by remembering every state and the step each key was
+/// first seen at. `None` if no key repeats within `limit` steps.
+pub fn find<S, K>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+    limit: usize,
+) -> Option<History<S>>
+where
+    K: Eq + Hash,
+{
+    let mut seen = FastHashMap::default();
+    let mut states = vec![];
+    let mut state = initial;
+    for i in 0..=limit {
+        if let Some(&start) = seen.get(&key(&state)) {
+            return Some(History { cycle: Cycle { start, length: i - start }, states });
+        }
+        seen.insert(key(&state), i);
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+    None
+}
+
+/// A cycle found by [`find`], and every state up to it.
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    pub cycle: Cycle,
+    /// The states from step 0 to the end of the first time round the cycle
+    pub states: Vec<S>,
+}
+
+impl<S> History<S> {
+    /// The state after `n` steps.
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.cycle.index(n)]
+    }
+
+    /// The total of `metric` over the states before step `n`, counting the
+    /// state at step 0 but not at step `n`. `None` if it overflows `T`.
+    pub fn total<T>(&self, n: usize, metric: impl FnMut(&S) -> T) -> Option<T>
+    where
+        T: Integer + TryFrom<usize>,
+    {
+        let values = self.states.iter().take(n).map(metric).collect::<Vec<_>>();
+        total(&self.cycle, n, &values)
+    }
+}
+
+/// The total over the first `n` steps, from the values of the steps up to
+/// the end of the first time round the cycle (or of all `n`, if fewer).
+fn total<T>(cycle: &Cycle, n: usize, values: &[T]) -> Option<T>
+where
+    T: Integer + TryFrom<usize>,
+{
+    let sum = |values: &[T]| values.iter().try_fold(T::ZERO, |sum, &value| sum.checked_add(value));
+    let Cycle { start, length } = *cycle;
+    if n <= start + length {
+        return sum(&values[..n]);
+    }
+    let laps = T::try_from((n - start) / length).ok()?;
+    let rest = (n - start) % length;
+    sum(&values[..start])?
+        .checked_add(sum(&values[start..])?.checked_mul(laps)?)?
+        .checked_add(sum(&values[start..start + rest])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// The state after `n` steps, by taking every one of them.
+    fn naive(n: usize, initial: u32, step: impl Fn(&u32) -> u32) -> u32 {
+        (0..n).fold(initial, |state, _| step(&state))
+    }
+
+    #[test]
+    fn keys_and_totals() {
+        // A counter that wraps at 5, carrying how many steps it has taken:
+        // only the counter decides what comes next
+        let step = |&(counter, steps): &(u8, u64)| ((counter + 1) % 5, steps + 1);
+        let counter = |state: &(u8, u64)| state.0;
+
+        let history = find((3, 0), step, counter, 10).unwrap();
+        assert_eq!(history.cycle, Cycle { start: 0, length: 5 });
+        assert_eq!(history.state_at(12), &(0, 2));
+        // 3 + 4 + 0 + 1 + 2 for every 5 steps, then 3 + 4 + 0
+        assert_eq!(history.total(1_000_003, |&(counter, _)| u64::from(counter)), Some(200_000 * 10 + 7));
+        assert_eq!(history.total(2, |&(counter, _)| u64::from(counter)), Some(7));
+        assert_eq!(history.total(usize::MAX, |&(counter, _)| u8::from(counter > 0)), None);
+
+        let cycle = brent((3, 0), step, counter, 20).unwrap();
+        assert_eq!(cycle, history.cycle);
+        assert_eq!(cycle.replay(12, (3, 0), step), (0, 2));
+        assert_eq!(cycle.total(1_000_003, (3, 0), step, |&(counter, _)| u64::from(counter)), Some(2_000_007));
+
+        // Never repeats
+        assert_eq!(brent(0_u64, |x| x + 1, |&x| x, 100), None);
+        assert!(find(0_u64, |x| x + 1, |&x| x, 100).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn skips_to_any_step(a in 1_u32..50, b in 0_u32..50, modulus in 1_u32..200, initial in 0_u32..200, n in 0_usize..1000) {
+            let step = |x: &u32| (a * x * x + b) % modulus;
+            let history = find(initial, step, |&x| x, 1000).unwrap();
+            let cycle = brent(initial, step, |&x| x, 1000).unwrap();
+            prop_assert_eq!(cycle, history.cycle);
+
+            let expected = naive(n, initial, step);
+            prop_assert_eq!(*history.state_at(n), expected);
+            prop_assert_eq!(cycle.replay(n, initial, step), expected);
+
+            let total = (0..n).map(|i| u64::from(naive(i, initial, step))).sum::<u64>();
+            prop_assert_eq!(history.total(n, |&x| u64::from(x)), Some(total));
+            prop_assert_eq!(cycle.total(n, initial, step, |&x| u64::from(x)), Some(total));
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// Where puzzle pages, inputs and leaderboards are downloaded from, unless
+/// `AOC_BASE_URL` says otherwise.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Downloads pages from the Advent of Code site and keeps a copy of each one
+/// on disk, so the site is only asked once for anything that does not change.
+pub struct Fetcher {
+    base_url: String,
+    session: Option<String>,
+    cache_dir: PathBuf,
+    agent: ureq::Agent,
+}
+
+impl Fetcher {
+    /// Creates a fetcher that caches responses in `cache_dir`. The base URL and
+    /// session cookie are read from `AOC_BASE_URL` and `AOC_SESSION`.
+    pub fn new<P: AsRef<Path>>(cache_dir: P) -> Self {
+        let base_url = std::env::var("AOC_BASE_URL")
+            .map_or_else(|_| DEFAULT_BASE_URL.to_string(), |url| url.trim_end_matches('/').to_string());
+        let session = std::env::var("AOC_SESSION").ok();
+
+        Self {
+            base_url,
+            session,
+            cache_dir: cache_dir.as_ref().to_path_buf(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .user_agent("github.com/stephenlf/rs-aoc-learn")
+                .build(),
+        }
+    }
+
+    /// Fetches from `base_url` instead, e.g. a local stand-in server in tests.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sends `session` as the session cookie, needed for inputs and private
+    /// leaderboards.
+    pub fn with_session(mut self, session: &str) -> Self {
+        self.session = Some(session.to_string());
+        self
+    }
+
+    /// Returns the page at `path` (e.g. `/2022/day/5`), downloading it only if
+    /// there is no cached copy. Pages that change once a puzzle is solved, or
+    /// over time, need [`Fetcher::get_fresh_within`] instead.
+    pub fn get(&self, path: &str) -> Result<String, FetchError> {
+        self.get_fresh_within(path, Duration::MAX)
+    }
+
+    /// Returns the page at `path`, downloading it again if the cached copy is
+    /// older than `max_age`. Use this for pages that change, like leaderboards.
+    pub fn get_fresh_within(&self, path: &str, max_age: Duration) -> Result<String, FetchError> {
+        let cached = self.cache_path(path);
+
+        let age = fs::metadata(&cached)
+            .and_then(|metadata| metadata.modified())
+            .map(|modified| SystemTime::now().duration_since(modified).unwrap_or_default());
+        if let Ok(age) = age {
+            if age < max_age {
+                tracing::debug!(path, ?age, "using cached copy");
+                return Ok(fs::read_to_string(cached)?);
+            }
+        }
+
+        let url = format!("{}{}", self.base_url, path);
+        tracing::info!(%url, "downloading");
+
+        let mut request = self.agent.get(&url);
+        if let Some(session) = &self.session {
+            request = request.set("Cookie", &format!("session={session}"));
+        }
+        let body = match request.call() {
+            Ok(response) => response.into_string()?,
+            Err(ureq::Error::Status(status, _)) => return Err(FetchError::Status(url, status)),
+            Err(e) => return Err(FetchError::Transport(e.to_string())),
+        };
+
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(cached, &body)?;
+
+        Ok(body)
+    }
+
+    /// Cached copy of `path`, e.g. `/2022/day/5` is kept as `2022_day_5`, or
+    /// as `2022_day_5_session` if fetched with a session cookie, since the
+    /// site shows more to a logged-in user.
+    fn cache_path(&self, path: &str) -> PathBuf {
+        let mut name = path.trim_matches('/')
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '_' })
+            .collect::<String>();
+        if self.session.is_some() {
+            name.push_str("_session");
+        }
+        self.cache_dir.join(name)
+    }
+}
+
+#[derive(Debug)]
+/// Error that may be thrown while downloading a page
+pub enum FetchError {
+    /// The server answered, but not with 200 OK. Holds the URL and status.
+    Status(String, u16),
+    /// The server could not be reached
+    Transport(String),
+    /// The cache could not be read or written
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Status(url, 400 | 401) =>
+                write!(f, "{url} was refused; is AOC_SESSION set to a valid session cookie?"),
+            Self::Status(url, status) => write!(f, "{url} answered with status {status}"),
+            Self::Transport(message) => write!(f, "could not connect: {message}"),
+            Self::Io(e) => write!(f, "cache error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<std::io::Error> for FetchError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// Serves `body` to the first `connections` requests, then stops. Returns
+    /// the base URL and a handle yielding the request lines it received.
+    fn stand_in(body: &'static str, connections: usize) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for stream in listener.incoming().take(connections) {
+                let mut stream = stream.unwrap();
+                let mut head = vec![];
+                for line in BufReader::new(&stream).lines() {
+                    let line = line.unwrap();
+                    if line.is_empty() {
+                        break;
+                    }
+                    head.push(line);
+                }
+                requests.push(head.join("\n"));
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn caches_responses() {
+        let (url, server) = stand_in("hello", 1);
+        let fetcher = Fetcher::new(cache_dir("aoc_fetch_caches"))
+            .with_base_url(&url)
+            .with_session("abc123");
+
+        assert_eq!(fetcher.get("/2022/day/1").unwrap(), "hello");
+        // The stand-in only answers once, so this must come from the cache.
+        assert_eq!(fetcher.get("/2022/day/1").unwrap(), "hello");
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2022/day/1 HTTP/1.1"));
+        assert!(requests[0].contains("session=abc123"));
+    }
+
+    #[test]
+    fn refreshes_stale_copies() {
+        let (url, server) = stand_in("fresh", 2);
+        let fetcher = Fetcher::new(cache_dir("aoc_fetch_refreshes")).with_base_url(&url);
+
+        fetcher.get("/board.json").unwrap();
+        fetcher.get_fresh_within("/board.json", Duration::ZERO).unwrap();
+
+        assert_eq!(server.join().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn caches_by_session() {
+        let (url, server) = stand_in("page", 2);
+        let dir = cache_dir("aoc_fetch_by_session");
+        Fetcher::new(&dir).with_base_url(&url).get("/2022/day/1").unwrap();
+        // A copy fetched without a session does not do for one with
+        let fetcher = Fetcher::new(&dir).with_base_url(&url).with_session("abc123");
+        fetcher.get("/2022/day/1").unwrap();
+        fetcher.get("/2022/day/1").unwrap();
+
+        assert_eq!(server.join().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,169 @@
+use std::{fmt::Display, fs, path::Path};
+
+/// Clean-up applied to an input before a solution sees it. A byte order mark
+/// is always removed and CRLF line endings always become LF; trimming is
+/// optional because some inputs (day 5's crate diagram) pad lines with spaces
+/// on purpose.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Normalize {
+    pub trim_trailing_whitespace: bool,
+}
+
+/// What normalising an input changed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    /// A byte order mark was removed from the start
+    pub bom: bool,
+    /// Number of CRLF line endings converted to LF
+    pub crlf: usize,
+    /// Number of lines that had trailing whitespace removed
+    pub trimmed: usize,
+}
+
+impl Report {
+    /// True if the input was already normalised.
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut changes = vec![];
+        if self.bom {
+            changes.push("removed byte order mark".to_string());
+        }
+        if self.crlf > 0 {
+            changes.push(format!("converted {} CRLF line endings", self.crlf));
+        }
+        if self.trimmed > 0 {
+            changes.push(format!("trimmed trailing whitespace from {} lines", self.trimmed));
+        }
+
+        if changes.is_empty() {
+            write!(f, "no changes")
+        } else {
+            write!(f, "{}", changes.join(", "))
+        }
+    }
+}
+
+/// Normalises `text` as described by `options`.
+///
+/// ```
+/// use aoc::input::{normalize, Normalize};
+///
+/// let (text, report) = normalize("\u{feff}1000\r\n2000\r\n", Normalize::default());
+/// assert_eq!(text, "1000\n2000\n");
+/// assert_eq!(report.to_string(), "removed byte order mark, converted 2 CRLF line endings");
+/// ```
+pub fn normalize(text: &str, options: Normalize) -> (String, Report) {
+    let mut report = Report::default();
+
+    let text = match text.strip_prefix('\u{feff}') {
+        Some(rest) => {
+            report.bom = true;
+            rest
+        }
+        None => text,
+    };
+
+    let mut normalized = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let (mut content, newline) = match line.strip_suffix('\n') {
+            Some(content) => (content, "\n"),
+            None => (line, ""),
+        };
+
+        if !newline.is_empty() {
+            if let Some(stripped) = content.strip_suffix('\r') {
+                report.crlf += 1;
+                content = stripped;
+            }
+        }
+
+        if options.trim_trailing_whitespace {
+            let trimmed = content.trim_end();
+            if trimmed.len() != content.len() {
+                report.trimmed += 1;
+                content = trimmed;
+            }
+        }
+
+        normalized.push_str(content);
+        normalized.push_str(newline);
+    }
+
+    (normalized, report)
+}
+
+/// Reads and normalises the input at `path`. Any changes are logged at debug
+/// level as well as returned.
+pub fn read<P: AsRef<Path>>(path: P, options: Normalize) -> std::io::Result<(String, Report)> {
+    let text = fs::read_to_string(&path)?;
+    let (text, report) = normalize(&text, options);
+
+    if !report.is_clean() {
+        tracing::debug!(path = %path.as_ref().display(), %report, "normalised input");
+    }
+
+    Ok((text, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIM: Normalize = Normalize { trim_trailing_whitespace: true };
+
+    #[test]
+    fn clean_input_is_unchanged() {
+        let text = "    [D]    \n[N] [C]    \n\n";
+        let (normalized, report) = normalize(text, Normalize::default());
+        assert_eq!(normalized, text);
+        assert!(report.is_clean());
+        assert_eq!(report.to_string(), "no changes");
+    }
+
+    #[test]
+    fn bom() {
+        let (normalized, report) = normalize("\u{feff}abc", Normalize::default());
+        assert_eq!(normalized, "abc");
+        assert_eq!(report, Report { bom: true, ..Default::default() });
+    }
+
+    #[test]
+    fn crlf() {
+        // A lone carriage return is not a line ending, so it is kept
+        let (normalized, report) = normalize("a\r\nb\rc\r\n\r\nd", Normalize::default());
+        assert_eq!(normalized, "a\nb\rc\n\nd");
+        assert_eq!(report.crlf, 3);
+    }
+
+    #[test]
+    fn trailing_whitespace() {
+        let (normalized, report) = normalize("a  \r\nb\t\nc\n \n", TRIM);
+        assert_eq!(normalized, "a\nb\nc\n\n");
+        assert_eq!(report, Report { bom: false, crlf: 1, trimmed: 3 });
+        assert_eq!(
+            report.to_string(),
+            "converted 1 CRLF line endings, trimmed trailing whitespace from 3 lines",
+        );
+
+        // Only when asked
+        let (normalized, _) = normalize("a  \n", Normalize::default());
+        assert_eq!(normalized, "a  \n");
+    }
+
+    #[test]
+    fn read_logs_changes() {
+        let path = std::env::temp_dir().join("aoc_input_read_logs_changes.txt");
+        fs::write(&path, "\u{feff}1\r\n2\r\n").unwrap();
+
+        let (result, logs) = crate::logging::capture(|| read(&path, Normalize::default()));
+        let (text, report) = result.unwrap();
+        assert_eq!(text, "1\n2\n");
+        assert_eq!(report.crlf, 2);
+        assert!(logs.contains("normalised input"));
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+/// A private leaderboard, as exported from the site's
+/// `/{year}/leaderboard/private/view/{id}.json` API.
+#[derive(Debug, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub members: HashMap<String, Member>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// Members who have not set a display name are anonymous.
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    /// Day -> part -> when that star was earned.
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Star {
+    /// Unix timestamp, in seconds.
+    pub get_star_ts: i64,
+}
+
+/// How long a member took on one day, counted from when the puzzle unlocked.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Completion {
+    pub part_one: Option<i64>,
+    pub part_two: Option<i64>,
+}
+
+impl Completion {
+    /// Seconds between earning the first and second star.
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.part_two? - self.part_one?)
+    }
+}
+
+impl Leaderboard {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Members ranked by local score, then stars, then name.
+    pub fn standings(&self) -> Vec<&Member> {
+        let mut members = self.members.values().collect::<Vec<_>>();
+        members.sort_by(|a, b| {
+            b.local_score.cmp(&a.local_score)
+                .then(b.stars.cmp(&a.stars))
+                .then(a.display_name().cmp(&b.display_name()))
+        });
+        members
+    }
+
+    /// Every day on which at least one member earned a star.
+    pub fn days(&self) -> Vec<u8> {
+        let mut days = self.members.values()
+            .flat_map(|member| member.completion_day_level.keys().copied())
+            .collect::<Vec<_>>();
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    /// Unix timestamp at which `day`'s puzzle unlocked: midnight US Eastern
+    /// (05:00 UTC) on that day of December.
+    pub fn unlock_time(&self, day: u8) -> i64 {
+        let year = self.event.parse::<i64>().unwrap_or(2022);
+        days_from_civil(year, 12, day as i64) * 86_400 + 5 * 3_600
+    }
+
+    /// How long `member` took on each part of `day`, if they finished it.
+    pub fn completion(&self, member: &Member, day: u8) -> Completion {
+        let unlock = self.unlock_time(day);
+        let part = |part: u8| {
+            member.completion_day_level.get(&day)?
+                .get(&part)
+                .map(|star| star.get_star_ts - unlock)
+        };
+        Completion { part_one: part(1), part_two: part(2) }
+    }
+
+    /// Renders the standings and per-day times as aligned plain text.
+    pub fn to_text(&self) -> String {
+        self.tables().iter()
+            .map(|(title, table)| format!("{title}\n{}", table.to_text()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Renders the standings and per-day times as Markdown, e.g. for a wiki.
+    pub fn to_markdown(&self) -> String {
+        let mut s = format!("# Leaderboard {}\n", self.event);
+        for (title, table) in self.tables() {
+            s.push_str(&format!("\n## {title}\n\n{}", table.to_markdown()));
+        }
+        s
+    }
+
+    /// The standings table, followed by one table per day.
+    fn tables(&self) -> Vec<(String, Table<4>)> {
+        let standings = self.standings();
+        let mut tables = vec![];
+
+        let mut table = Table::new(["Rank", "Name", "Stars", "Local score"]);
+        for (rank, member) in standings.iter().enumerate() {
+            table.push([
+                (rank + 1).to_string(),
+                member.display_name(),
+                member.stars.to_string(),
+                member.local_score.to_string(),
+            ]);
+        }
+        tables.push(("Standings".to_string(), table));
+
+        for day in self.days() {
+            let mut table = Table::new(["Name", "Part 1", "Part 2", "Delta"]);
+            for member in &standings {
+                let completion = self.completion(member, day);
+                if completion.part_one.is_none() {
+                    continue;
+                }
+                table.push([
+                    member.display_name(),
+                    format_duration(completion.part_one),
+                    format_duration(completion.part_two),
+                    format_duration(completion.delta()),
+                ]);
+            }
+            tables.push((format!("Day {day}"), table));
+        }
+
+        tables
+    }
+}
+
+impl Member {
+    /// The member's name, or how the site refers to anonymous members.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+}
+
+/// Formats seconds as `HH:MM:SS`, with a day count once it exceeds 24 hours.
+fn format_duration(seconds: Option<i64>) -> String {
+    let Some(seconds) = seconds else {
+        return "-".to_string();
+    };
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    let time = format!("{:02}:{:02}:{:02}", seconds / 3_600, seconds % 3_600 / 60, seconds % 60);
+    if days > 0 {
+        format!("{days}d {time}")
+    } else {
+        time
+    }
+}
+
+/// Days since 1970-01-01 of the given date in the proleptic Gregorian
+/// calendar. See http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// A header row and body rows of text, rendered either aligned or as Markdown.
+struct Table<const N: usize> {
+    header: [&'static str; N],
+    rows: Vec<[String; N]>,
+}
+
+impl<const N: usize> Table<N> {
+    fn new(header: [&'static str; N]) -> Self {
+        Self { header, rows: vec![] }
+    }
+
+    fn push(&mut self, row: [String; N]) {
+        self.rows.push(row);
+    }
+
+    fn to_text(&self) -> String {
+        let mut widths = self.header.map(str::len);
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: Vec<&str>| {
+            let line = cells.iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        };
+
+        let mut s = line(self.header.to_vec());
+        for row in &self.rows {
+            s.push_str(&line(row.iter().map(String::as_str).collect()));
+        }
+        s
+    }
+
+    fn to_markdown(&self) -> String {
+        let line = |cells: Vec<&str>| format!("| {} |\n", cells.join(" | "));
+
+        let mut s = line(self.header.to_vec());
+        s.push_str(&line(vec!["---"; N]));
+        for row in &self.rows {
+            // Pipes would end the cell early
+            let row = row.iter().map(|cell| cell.replace('|', "\\|")).collect::<Vec<_>>();
+            s.push_str(&line(row.iter().map(String::as_str).collect()));
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Day 1 of 2022 unlocked at 1669870800 (2022-12-01T05:00:00Z).
+    const JSON: &str = r#"{
+        "owner_id": 1,
+        "event": "2022",
+        "members": {
+            "1": {
+                "id": 1, "name": "Alice", "stars": 3, "local_score": 10,
+                "global_score": 0, "last_star_ts": 1669960000,
+                "completion_day_level": {
+                    "1": {
+                        "1": { "get_star_ts": 1669871100, "star_index": 0 },
+                        "2": { "get_star_ts": 1669871400, "star_index": 1 }
+                    },
+                    "2": {
+                        "1": { "get_star_ts": 1670043600, "star_index": 2 }
+                    }
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 2, "local_score": 10,
+                "global_score": 0, "last_star_ts": 1669960000,
+                "completion_day_level": {
+                    "1": {
+                        "1": { "get_star_ts": 1669957200, "star_index": 3 },
+                        "2": { "get_star_ts": 1669960000, "star_index": 4 }
+                    }
+                }
+            },
+            "3": {
+                "id": 3, "name": "Carol", "stars": 0, "local_score": 0,
+                "global_score": 0, "last_star_ts": 0,
+                "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn unlock_time() {
+        let leaderboard = Leaderboard::from_json(JSON).unwrap();
+        assert_eq!(leaderboard.unlock_time(1), 1_669_870_800);
+        assert_eq!(leaderboard.unlock_time(25), 1_669_870_800 + 24 * 86_400);
+    }
+
+    #[test]
+    fn standings() {
+        let leaderboard = Leaderboard::from_json(JSON).unwrap();
+        let names = leaderboard.standings().iter()
+            .map(|member| member.display_name())
+            .collect::<Vec<_>>();
+        // Ties on score are broken by stars
+        assert_eq!(names, ["Alice", "(anonymous user #2)", "Carol"]);
+        assert_eq!(leaderboard.days(), [1, 2]);
+    }
+
+    #[test]
+    fn completion() {
+        let leaderboard = Leaderboard::from_json(JSON).unwrap();
+        let alice = &leaderboard.members["1"];
+
+        let day_1 = leaderboard.completion(alice, 1);
+        assert_eq!(day_1, Completion { part_one: Some(300), part_two: Some(600) });
+        assert_eq!(day_1.delta(), Some(300));
+
+        let day_2 = leaderboard.completion(alice, 2);
+        assert_eq!(day_2.part_two, None);
+        assert_eq!(day_2.delta(), None);
+    }
+
+    #[test]
+    fn to_text() {
+        let leaderboard = Leaderboard::from_json(JSON).unwrap();
+        let text = leaderboard.to_text();
+        assert!(text.starts_with("Standings\nRank  Name                 Stars  Local score\n"));
+        assert!(text.contains("\n\
+Day 1
+Name                 Part 1       Part 2       Delta
+Alice                00:05:00     00:10:00     00:05:00
+(anonymous user #2)  1d 00:00:00  1d 00:46:40  00:46:40
+"));
+        assert!(text.contains("\nAlice  1d 00:00:00  -       -\n"));
+    }
+
+    #[test]
+    fn to_markdown() {
+        let markdown = Leaderboard::from_json(JSON).unwrap().to_markdown();
+        assert!(markdown.starts_with("# Leaderboard 2022\n\n## Standings\n\n| Rank | Name | Stars | Local score |\n| --- | --- | --- | --- |\n| 1 | Alice | 3 | 10 |\n"));
+        assert!(markdown.contains("\n## Day 2\n\n| Name | Part 1 | Part 2 | Delta |\n| --- | --- | --- | --- |\n| Alice | 1d 00:00:00 | - | - |\n"));
+    }
+
+    #[test]
+    fn format_duration() {
+        assert_eq!(super::format_duration(Some(59)), "00:00:59");
+        assert_eq!(super::format_duration(Some(90_061)), "1d 01:01:01");
+        assert_eq!(super::format_duration(None), "-");
+    }
+}
@@ -1,16 +1,52 @@
-pub type LinesIter = std::io::Lines<std::io::BufReader<std::fs::File>>;
+// Lets the code `AocParse` generates, which names `::aoc`, work in here too
+extern crate self as aoc;
 
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    path::PathBuf,
-};
+/// Lines of an input. Parsers take any reader's lines, so tests can feed them
+/// an inline string (see [`str_as_lines`]) instead of a file.
+pub type LinesIter<R = std::io::Cursor<String>> = std::io::Lines<R>;
+
+pub mod answer;
+pub mod collections;
+pub mod cycle;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod input;
+#[cfg(feature = "leaderboard")]
+pub mod leaderboard;
+pub mod logging;
+pub mod math;
+pub mod parse;
+pub mod puzzle;
+pub mod schema;
+pub mod simulation;
+pub mod snapshot;
+pub use answer::Answer;
+pub use aoc_derive::AocParse;
+pub use parse::ParseError;
+
+use std::io::{BufRead, Cursor};
 
 /// Takes in the path to a file, and returns an iterator over the lines of that
 /// file. File paths may be absolute or relative to the package, and should be
-/// forward-slash delimited (even on Windows systems.) 
+/// forward-slash delimited (even on Windows systems.) The file is normalised
+/// first (see [`input::normalize`]), so a byte order mark or CRLF line endings
+/// left behind by a Windows editor are removed.
 pub fn read_as_lines<T: ToString>(path: T) -> Result<LinesIter, std::io::Error> {
-    let file = File::open(PathBuf::from(path.to_string()))?;
+    // `read` has logged what normalising changed, at debug level
+    let (text, _report) = input::read(path.to_string(), input::Normalize::default())?;
+
+    Ok(Cursor::new(text).lines())
+}
+
+/// Returns an iterator over the lines of `text`, normalised the same way as
+/// [`read_as_lines`] does a file. Handy for trying a parser on an example.
+///
+/// ```
+/// let lines = aoc::str_as_lines("\u{feff}1000\r\n2000\r\n");
+/// assert_eq!(lines.map(Result::unwrap).collect::<Vec<_>>(), ["1000", "2000"]);
+/// ```
+pub fn str_as_lines(text: &str) -> LinesIter {
+    let (text, _) = input::normalize(text, input::Normalize::default());
 
-    Ok(BufReader::new(file).lines())
-}
\ No newline at end of file
+    Cursor::new(text).lines()
+}
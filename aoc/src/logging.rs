@@ -0,0 +1,157 @@
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use tracing_subscriber::{filter::ParseError, EnvFilter};
+
+/// Installs a global subscriber that prints events to stderr, leaving stdout
+/// for answers. While a [`hold`] is in place they are kept back instead.
+///
+/// `verbosity` sets the level for every target: 0 shows warnings, 1 (`-v`)
+/// info, 2 (`-vv`) debug and 3 or more trace. `RUST_LOG` and then
+/// `directives` are applied on top, so a single day can be turned up with
+/// e.g. `day_7=trace`. Every day logs under its crate name.
+///
+/// Does nothing if a global subscriber is already installed.
+pub fn init(verbosity: u8, directives: Option<&str>) -> Result<(), ParseError> {
+    let level = match verbosity {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+    let env = std::env::var(EnvFilter::DEFAULT_ENV).ok();
+
+    let filter = [Some(level), env.as_deref(), directives]
+        .into_iter()
+        .flatten()
+        .filter(|directive| !directive.is_empty())
+        .collect::<Vec<&str>>()
+        .join(",");
+    let filter = EnvFilter::try_new(filter)?;
+
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(|| Stderr)
+        .try_init();
+
+    Ok(())
+}
+
+/// What `init`'s subscriber has written while held, or `None` if it is not.
+static HELD: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+
+/// Keeps the events `init`'s subscriber would print to stderr back until the
+/// returned guard is dropped, then prints them all. For full-screen
+/// interfaces, which anything printed meanwhile would draw over.
+pub fn hold() -> Held {
+    HELD.lock().unwrap().get_or_insert_with(Vec::new);
+    Held(())
+}
+
+/// Prints the held events when dropped. See [`hold`].
+#[must_use = "events are printed as soon as the guard is dropped"]
+pub struct Held(());
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        if let Some(held) = HELD.lock().unwrap().take() {
+            let _ = std::io::stderr().write_all(&held);
+        }
+    }
+}
+
+/// Stderr, or the held events if there is a [`hold`].
+struct Stderr;
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match HELD.lock().unwrap().as_mut() {
+            Some(held) => {
+                held.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            None => std::io::stderr().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stderr().flush()
+    }
+}
+
+/// Runs `f` with every event, down to trace level, recorded on the current
+/// thread. Returns the result of `f` and the recorded events, one per line.
+///
+/// ```
+/// let (answer, logs) = aoc::logging::capture(|| {
+///     tracing::warn!(line = 3, "could not parse");
+///     42
+/// });
+/// assert_eq!(answer, 42);
+/// assert!(logs.contains("could not parse line=3"));
+/// ```
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, String) {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .without_time()
+        .finish();
+
+    let result = tracing::subscriber::with_default(subscriber, f);
+    let logs = String::from_utf8_lossy(&buffer.0.lock().unwrap()).to_string();
+
+    (result, logs)
+}
+
+/// Shared, growable byte buffer that the capturing subscriber writes into.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_every_level() {
+        let ((), logs) = capture(|| {
+            tracing::trace!(cycle = 1, "tick");
+            tracing::error!("boom");
+        });
+        let lines = logs.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("TRACE"));
+        assert!(lines[0].contains("tick cycle=1"));
+        assert!(lines[1].contains("ERROR"));
+    }
+
+    #[test]
+    fn holds_until_dropped() {
+        let held = hold();
+        Stderr.write_all(b"kept back").unwrap();
+        assert_eq!(HELD.lock().unwrap().as_deref(), Some(&b"kept back"[..]));
+        drop(held);
+        assert_eq!(*HELD.lock().unwrap(), None);
+    }
+
+    #[test]
+    fn bad_directive() {
+        assert!(init(0, Some("day_7=loud")).is_err());
+    }
+}
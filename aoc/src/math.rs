@@ -0,0 +1,345 @@
+//! Number theory that keeps coming up in the puzzles: greatest common
+//! divisors and least common multiples, arithmetic modulo a number, the
+//! Chinese remainder theorem and integer square roots. Everything works on
+//! every primitive integer type, and nothing overflows: where the answer
+//! does not fit, or there is none, the result is `None`.
+//!
+//! ```
+//! use aoc::math;
+//!
+//! // Day 11 keeps worry levels small by reducing them modulo every test's
+//! // divisor at once
+//! assert_eq!(math::lcm_all([23_u64, 19, 13, 17]), Some(96577));
+//!
+//! assert_eq!(math::pow_mod(3_u8, 200, 251), Some(149));
+//! assert_eq!(math::crt([(2_i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+//! assert_eq!(math::isqrt(99_u32), 9);
+//! ```
+
+use std::{
+    fmt::Debug,
+    ops::{Add, Div, Mul, Rem, Sub},
+};
+
+/// A primitive integer, signed or not.
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    /// The remainder of dividing by `modulus`, which is never negative.
+    fn rem_euclid(self, modulus: Self) -> Self;
+
+    /// The absolute value.
+    ///
+    /// # Panics
+    ///
+    /// On the smallest value of a signed type, whose absolute value does not
+    /// fit (in debug builds, like `i32::abs`).
+    fn abs(self) -> Self {
+        if self < Self::ZERO { Self::ZERO - self } else { self }
+    }
+}
+
+macro_rules! integer {
+    ($($t:ty)*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn rem_euclid(self, modulus: Self) -> Self {
+                <$t>::rem_euclid(self, modulus)
+            }
+        }
+    )*};
+}
+
+integer!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+/// The greatest common divisor of `a` and `b`, which is never negative.
+/// `gcd(0, 0)` is 0.
+///
+/// # Panics
+///
+/// If either is the smallest value of a signed type (see [`Integer::abs`]).
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The greatest common divisor of all the `numbers`, or 0 if there are none.
+pub fn gcd_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+    numbers.into_iter().fold(T::ZERO, gcd)
+}
+
+/// The least common multiple of `a` and `b`, which is never negative, or
+/// `None` if it does not fit. It is 0 if either is.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a.abs() / gcd(a, b)).checked_mul(b.abs())
+}
+
+/// The least common multiple of all the `numbers`, or 1 if there are none,
+/// or `None` if it does not fit.
+pub fn lcm_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> Option<T> {
+    numbers.into_iter().try_fold(T::ONE, lcm)
+}
+
+/// `a + b` modulo `modulus`, or `None` if the modulus is not positive.
+pub fn add_mod<T: Integer>(a: T, b: T, modulus: T) -> Option<T> {
+    positive(modulus).map(|m| add(a.rem_euclid(m), b.rem_euclid(m), m))
+}
+
+/// `a - b` modulo `modulus`, or `None` if the modulus is not positive.
+pub fn sub_mod<T: Integer>(a: T, b: T, modulus: T) -> Option<T> {
+    positive(modulus).map(|m| sub(a.rem_euclid(m), b.rem_euclid(m), m))
+}
+
+/// `a * b` modulo `modulus`, or `None` if the modulus is not positive. Works
+/// even where `a * b` itself would overflow.
+pub fn mul_mod<T: Integer>(a: T, b: T, modulus: T) -> Option<T> {
+    positive(modulus).map(|m| mul(a.rem_euclid(m), b.rem_euclid(m), m))
+}
+
+/// `base` to the power `exponent` modulo `modulus`, or `None` if the modulus
+/// is not positive or the exponent is negative.
+pub fn pow_mod<T: Integer>(base: T, exponent: T, modulus: T) -> Option<T> {
+    let m = positive(modulus)?;
+    if exponent < T::ZERO {
+        return None;
+    }
+    let two = T::ONE + T::ONE;
+    let (mut base, mut exponent) = (base.rem_euclid(m), exponent);
+    let mut power = T::ONE % m;
+    while exponent > T::ZERO {
+        if exponent % two == T::ONE {
+            power = mul(power, base, m);
+        }
+        base = mul(base, base, m);
+        exponent = exponent / two;
+    }
+    Some(power)
+}
+
+/// The `x` with `a * x` ≡ 1 modulo `modulus`, between 0 and the modulus.
+/// `None` if the modulus is not positive, or `a` shares a factor with it, so
+/// has no inverse.
+pub fn mod_inverse<T: Integer>(a: T, modulus: T) -> Option<T> {
+    let m = positive(modulus)?;
+
+    // The extended Euclidean algorithm, keeping only the coefficients of
+    // `a`, modulo `m` so that they stay in range: s * a ≡ r throughout
+    let (mut r0, mut r1) = (m, a.rem_euclid(m));
+    let (mut s0, mut s1) = (T::ZERO, T::ONE % m);
+    while r1 != T::ZERO {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, sub(s0, mul(q % m, s1, m), m));
+    }
+    (r0 == T::ONE).then_some(s0)
+}
+
+/// Solves the congruences `x ≡ residue` modulo `modulus` for each pair of
+/// `congruences` by the Chinese remainder theorem. Returns the smallest
+/// `x` that is not negative, and the least common multiple of the moduli,
+/// as every solution is `x` plus a multiple of that.
+///
+/// The moduli need not be coprime. `None` if there is no solution, a
+/// modulus is not positive, or the moduli's least common multiple does not
+/// fit.
+pub fn crt<T: Integer>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let (mut x, mut m) = (T::ZERO, T::ONE);
+    for (residue, modulus) in congruences {
+        let n = positive(modulus)?;
+
+        // x + m * k ≡ residue (mod n), so m/g * k ≡ (residue - x)/g (mod n/g)
+        let g = gcd(m, n);
+        let difference = sub(residue.rem_euclid(n), x.rem_euclid(n), n);
+        if difference % g != T::ZERO {
+            return None;
+        }
+        let n_g = n / g;
+        let k = mul((difference / g) % n_g, mod_inverse(m / g, n_g)?, n_g);
+
+        // As k < n/g, x stays below the new modulus, lcm(m, n)
+        let lcm = (m / g).checked_mul(n)?;
+        x = x + m * k;
+        m = lcm;
+    }
+    Some((x, m))
+}
+
+/// The largest integer whose square is at most `n`.
+///
+/// # Panics
+///
+/// If `n` is negative.
+pub fn isqrt<T: Integer>(n: T) -> T {
+    assert!(n >= T::ZERO, "isqrt of negative number {n:?}");
+    let two = T::ONE + T::ONE;
+    let (mut low, mut high) = (T::ZERO, n);
+    while low < high {
+        let middle = low + (high - low) / two + T::ONE;
+        match middle.checked_mul(middle) {
+            Some(square) if square <= n => low = middle,
+            _ => high = middle - T::ONE,
+        }
+    }
+    low
+}
+
+fn positive<T: Integer>(modulus: T) -> Option<T> {
+    (modulus > T::ZERO).then_some(modulus)
+}
+
+// Arithmetic on numbers already reduced modulo `m`, written so that nothing
+// larger than `m` is ever formed
+
+fn add<T: Integer>(a: T, b: T, m: T) -> T {
+    if a >= m - b { a - (m - b) } else { a + b }
+}
+
+fn sub<T: Integer>(a: T, b: T, m: T) -> T {
+    if a >= b { a - b } else { a + (m - b) }
+}
+
+fn mul<T: Integer>(a: T, b: T, m: T) -> T {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    // Double and add, a bit of `b` at a time
+    let two = T::ONE + T::ONE;
+    let (mut a, mut b, mut product) = (a, b, T::ZERO);
+    while b > T::ZERO {
+        if b % two == T::ONE {
+            product = add(product, a, m);
+        }
+        a = add(a, a, m);
+        b = b / two;
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn edges() {
+        assert_eq!(gcd(0_u8, 0), 0);
+        assert_eq!(gcd(-12_i8, 18), 6);
+        assert_eq!(gcd_all(Vec::<u16>::new()), 0);
+        assert_eq!(lcm(-4_i32, 6), Some(12));
+        assert_eq!(lcm(0_u8, 7), Some(0));
+        assert_eq!(lcm(16_u8, 17), None);
+        assert_eq!(lcm_all([2_u8, 3, 5, 7]), Some(210));
+        assert_eq!(lcm_all([2_u8, 3, 5, 7, 11]), None);
+
+        assert_eq!(add_mod(250_u8, 250, 251), Some(249));
+        assert_eq!(sub_mod(-3_i8, 5, 7), Some(6));
+        assert_eq!(mul_mod(u64::MAX, u64::MAX, u64::MAX - 1), Some(1));
+        assert_eq!(mul_mod(3_i32, 4, 0), None);
+        assert_eq!(pow_mod(2_i16, -1, 5), None);
+        assert_eq!(pow_mod(7_u8, 0, 1), Some(0));
+
+        assert_eq!(mod_inverse(3_u8, 7), Some(5));
+        assert_eq!(mod_inverse(-3_i8, 7), Some(2));
+        assert_eq!(mod_inverse(4_u8, 6), None);
+        assert_eq!(mod_inverse(5_u32, 1), Some(0));
+
+        assert_eq!(crt(Vec::<(u8, u8)>::new()), Some((0, 1)));
+        assert_eq!(crt([(1_u32, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1_u32, 4), (2, 6)]), None);
+        assert_eq!(crt([(0_u8, 16), (0, 17)]), None);
+
+        assert_eq!(isqrt(0_u8), 0);
+        assert_eq!(isqrt(u8::MAX), 15);
+        assert_eq!(isqrt(i64::MAX), 3_037_000_499);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    proptest! {
+        #[test]
+        fn gcd_and_lcm(a in -100_000_i64..100_000, b in -100_000_i64..100_000) {
+            let g = gcd(a, b);
+            prop_assert!(g >= 0);
+            if g > 0 {
+                prop_assert_eq!((a % g, b % g), (0, 0));
+                prop_assert_eq!(gcd(a / g, b / g), 1);
+            }
+            prop_assert_eq!(g * lcm(a, b).unwrap(), (a * b).abs());
+        }
+
+        #[test]
+        fn modular_arithmetic(a: u64, b: u64, m in 1_u64..) {
+            let wide = |n: u128| Some((n % m as u128) as u64);
+            prop_assert_eq!(add_mod(a, b, m), wide(a as u128 + b as u128));
+            prop_assert_eq!(mul_mod(a, b, m), wide(a as u128 * b as u128));
+            prop_assert_eq!(sub_mod(a, b, m), wide(a as u128 + (m - b % m) as u128));
+        }
+
+        #[test]
+        fn signed_modular_arithmetic(a: i32, b: i32, m in 1_i32..) {
+            let wide = |n: i64| Some(n.rem_euclid(m as i64) as i32);
+            prop_assert_eq!(add_mod(a, b, m), wide(a as i64 + b as i64));
+            prop_assert_eq!(sub_mod(a, b, m), wide(a as i64 - b as i64));
+            prop_assert_eq!(mul_mod(a, b, m), wide(a as i64 * b as i64));
+        }
+
+        #[test]
+        fn powers(base: u32, exponent in 0_u32..200, m in 1_u32..) {
+            let expected = (0..exponent).fold(1 % m as u64, |power, _| power * base as u64 % m as u64);
+            prop_assert_eq!(pow_mod(base, exponent, m), Some(expected as u32));
+        }
+
+        #[test]
+        fn inverses(a: i64, m in 1_i64..) {
+            match mod_inverse(a, m) {
+                Some(inverse) => {
+                    prop_assert!((0..m).contains(&inverse));
+                    prop_assert_eq!(mul_mod(a, inverse, m), Some(1 % m));
+                }
+                None => prop_assert_ne!(gcd(a.rem_euclid(m), m), 1),
+            }
+        }
+
+        #[test]
+        fn chinese_remainders(x in 0_u64..1_000_000_000, moduli in prop::collection::vec(1_u64..1_000, 0..4)) {
+            let congruences = moduli.iter().map(|&m| (x % m, m)).collect::<Vec<_>>();
+            let lcm = lcm_all(moduli.iter().copied()).unwrap();
+            prop_assert_eq!(crt(congruences), Some((x % lcm, lcm)));
+        }
+
+        #[test]
+        fn square_roots(n: u64) {
+            let root = isqrt(n) as u128;
+            prop_assert!(root * root <= n as u128);
+            prop_assert!((root + 1) * (root + 1) > n as u128);
+        }
+    }
+}
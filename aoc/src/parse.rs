@@ -0,0 +1,193 @@
+//! Parsing lines against templates, for `#[derive(AocParse)]` (see
+//! [`AocParse`](crate::AocParse)).
+//!
+//! ```
+//! use aoc::AocParse;
+//!
+//! #[derive(AocParse, Debug, PartialEq)]
+//! #[aoc(format = "move {count} from {from} to {to}")]
+//! struct Command {
+//!     count: u32,
+//!     from: usize,
+//!     to: usize,
+//! }
+//!
+//! let command = "move 3 from 1 to 2".parse::<Command>().unwrap();
+//! assert_eq!(command, Command { count: 3, from: 1, to: 2 });
+//!
+//! let error = "move 3 form 1 to 2".parse::<Command>().unwrap_err();
+//! assert_eq!(error.to_string(), "column 6: expected \" from \" after `count`, found \"3 form 1 to 2\"");
+//! ```
+
+use std::{any::Any, fmt::Display, str::FromStr};
+
+/// Why a line did not match its template, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the input, counting from 1, if known
+    pub line: Option<usize>,
+    /// Character in the line where it stopped matching, counting from 1
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(column: usize, message: impl Into<String>) -> Self {
+        Self { line: None, column, message: message.into() }
+    }
+
+    /// Places the error on line `line` of the input.
+    pub fn at_line(self, line: usize) -> Self {
+        Self { line: Some(line), ..self }
+    }
+
+    /// Of `best` and `other`, the error that got further into the line. Used
+    /// to report why no variant of an enum matched.
+    #[doc(hidden)]
+    pub fn furthest(best: Option<Self>, other: Self) -> Option<Self> {
+        match best {
+            Some(best) if best.column >= other.column => Some(best),
+            _ => Some(other),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}, ")?;
+        }
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// `error` as a `ParseError` at `column`, worded by `describe`. If it is a
+/// `ParseError` already, it keeps its message and is moved along to start
+/// from `column`.
+pub(crate) fn located<E: Display + 'static>(error: E, column: usize, describe: impl FnOnce(&E) -> String) -> ParseError {
+    match (&error as &dyn Any).downcast_ref::<ParseError>() {
+        Some(inner) => ParseError { column: column + inner.column - 1, ..inner.clone() },
+        None => ParseError::new(column, describe(&error)),
+    }
+}
+
+/// Position in a line being matched against a template. The code that
+/// `#[derive(AocParse)]` writes walks the template with it.
+#[doc(hidden)]
+pub struct Cursor<'a> {
+    line: &'a str,
+    at: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(line: &'a str) -> Self {
+        Self { line, at: 0 }
+    }
+
+    /// Consumes `text`, which must come next.
+    pub fn text(&mut self, text: &str) -> Result<(), ParseError> {
+        let rest = &self.line[self.at..];
+        if !rest.starts_with(text) {
+            return Err(self.error(format!("expected {text:?}, found {rest:?}")));
+        }
+        self.at += text.len();
+        Ok(())
+    }
+
+    /// Parses the field `name`, which runs up to the first `until` or, if
+    /// that is `None`, the end of the line.
+    pub fn field<T>(&mut self, name: &str, until: Option<&str>) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display + 'static,
+    {
+        let rest = &self.line[self.at..];
+        let end = match until {
+            Some(until) => rest.find(until)
+                .ok_or_else(|| self.error(format!("expected {until:?} after `{name}`, found {rest:?}")))?,
+            None => rest.len(),
+        };
+        let value = &rest[..end];
+        // A field parsed with its own template says where in the field it
+        // went wrong; place that in the whole line
+        let parsed = value.parse::<T>()
+            .map_err(|e| located(e, self.column(), |e| format!("invalid `{name}` {value:?}: {e}")))?;
+        self.at += end;
+        Ok(parsed)
+    }
+
+    /// Checks that the whole line was matched.
+    pub fn finish(&self) -> Result<(), ParseError> {
+        let rest = &self.line[self.at..];
+        if rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format!("unexpected {rest:?} at the end")))
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.line[..self.at].chars().count() + 1
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        ParseError::new(self.column(), message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AocParse;
+
+    #[derive(AocParse, Debug, PartialEq)]
+    #[aoc(format = "{0}-{1}")]
+    struct Range(u32, u32);
+
+    #[derive(AocParse, Debug, PartialEq)]
+    #[aoc(format = "{left},{right}")]
+    struct Pair {
+        left: Range,
+        right: Range,
+    }
+
+    #[derive(AocParse, Debug, PartialEq)]
+    enum Instruction {
+        #[aoc(format = "noop")]
+        Noop,
+        #[aoc(format = "addx {0}")]
+        Addx(i32),
+        #[aoc(format = "{{{name}}} = {value}")]
+        Set { name: char, value: i64 },
+    }
+
+    #[test]
+    fn structs() {
+        assert_eq!("2-4".parse(), Ok(Range(2, 4)));
+        assert_eq!("2-4,6-8".parse(), Ok(Pair { left: Range(2, 4), right: Range(6, 8) }));
+
+        let error = "2-4,6x8".parse::<Pair>().unwrap_err();
+        assert_eq!(error.column, 5);
+        assert_eq!(error.to_string(), "column 5: expected \"-\" after `0`, found \"6x8\"");
+
+        let error = "2-four".parse::<Range>().unwrap_err();
+        assert_eq!(error.column, 3);
+        assert!(error.message.starts_with("invalid `1` \"four\""), "{error}");
+
+        assert_eq!("2-4 ".parse::<Range>().unwrap_err().to_string(), "column 3: invalid `1` \"4 \": invalid digit found in string");
+    }
+
+    #[test]
+    fn enums() {
+        assert_eq!("noop".parse(), Ok(Instruction::Noop));
+        assert_eq!("addx -7".parse(), Ok(Instruction::Addx(-7)));
+        assert_eq!("{x} = 12".parse(), Ok(Instruction::Set { name: 'x', value: 12 }));
+
+        // The variant that matched the most reports the error
+        let error = "addx seven".parse::<Instruction>().unwrap_err();
+        assert_eq!(error.column, 6);
+        assert_eq!(error.at_line(3).to_string(), "line 3, column 6: invalid `0` \"seven\": invalid digit found in string");
+        assert_eq!("noop!".parse::<Instruction>().unwrap_err().message, "unexpected \"!\" at the end");
+    }
+}
@@ -0,0 +1,369 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A puzzle description, converted from the page on the Advent of Code site.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Puzzle {
+    /// e.g. `Day 5: Supply Stacks`
+    pub title: String,
+    /// Both parts of the description (if the page shows both), as Markdown.
+    pub markdown: String,
+    /// The contents of every preformatted block, in page order. These are the
+    /// example inputs and, sometimes, diagrams of intermediate steps.
+    pub examples: Vec<String>,
+}
+
+impl Puzzle {
+    /// Converts the `<article>` elements of a puzzle page. Only the handful of
+    /// tags the site uses are understood; anything else is dropped and its text
+    /// kept.
+    pub fn from_html(html: &str) -> Self {
+        let mut writer = Writer::default();
+        for article in articles(html) {
+            for token in tokens(article) {
+                writer.write(token);
+            }
+        }
+
+        let title = writer.markdown.lines()
+            .find_map(|line| line.strip_prefix("## "))
+            .unwrap_or_default()
+            .trim_matches(|c| c == '-' || c == ' ')
+            .to_string();
+
+        Self {
+            title,
+            markdown: writer.markdown.trim_end().to_string() + "\n",
+            examples: writer.examples,
+        }
+    }
+
+    /// Whether the page showed part two, which it only does once part one is
+    /// solved by whoever's session fetched it.
+    pub fn has_part_two(&self) -> bool {
+        self.markdown.lines().any(|line| line.starts_with("## ") && line.contains("Part Two"))
+    }
+
+    /// Writes each example to `dir/example_N.txt`, counting from 1, and returns
+    /// the paths written.
+    pub fn write_examples<P: AsRef<Path>>(&self, dir: P) -> std::io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(&dir)?;
+        let mut paths = vec![];
+        for (i, example) in self.examples.iter().enumerate() {
+            let path = dir.as_ref().join(format!("example_{}.txt", i + 1));
+            fs::write(&path, example)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+/// The contents of every `<article>` element, skipping commented-out ones.
+fn articles(html: &str) -> Vec<&str> {
+    let mut articles = vec![];
+    let mut rest = html;
+    while let Some(start) = rest.find("<article") {
+        if let Some(comment) = rest[..start].rfind("<!--") {
+            if !rest[comment..start].contains("-->") {
+                rest = rest[start..].find("-->").map_or("", |end| &rest[start + end + 3..]);
+                continue;
+            }
+        }
+        rest = &rest[start..];
+        let Some(open_end) = rest.find('>') else { break };
+        let Some(end) = rest.find("</article>") else { break };
+        articles.push(&rest[open_end + 1..end]);
+        rest = &rest[end..];
+    }
+    articles
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    /// Tag name and the raw attributes following it
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            // Not a tag after all
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        let tag = rest[1..end].trim_end_matches('/');
+        rest = &rest[end + 1..];
+
+        match tag.strip_prefix('/') {
+            Some(name) => tokens.push(Token::Close(name.trim())),
+            None => {
+                let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+                tokens.push(Token::Open(name, attrs));
+            }
+        }
+    }
+    tokens
+}
+
+/// Value of attribute `name`, e.g. `href` in `href="/2022/day/5/input"`.
+fn attribute<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let start = attrs.find(&format!("{name}=\""))? + name.len() + 2;
+    let len = attrs[start..].find('"')?;
+    Some(&attrs[start..start + len])
+}
+
+/// Replaces the character references the site uses with the characters.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let c = entity.and_then(|(name, _)| match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => name.strip_prefix('#')?.parse().ok(),
+                };
+                char::from_u32(code?)
+            }
+        });
+
+        match (c, entity) {
+            (Some(c), Some((_, end))) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Builds the Markdown one token at a time. Inline text is gathered into
+/// `block` and written out, whitespace collapsed, when its block ends.
+#[derive(Default)]
+struct Writer {
+    markdown: String,
+    examples: Vec<String>,
+    block: String,
+    /// Text of the `<pre>` block being read, kept verbatim
+    pre: Option<String>,
+    /// Depth of `<code>` elements; emphasis can't be shown inside code spans
+    code: usize,
+    /// Depth of nested lists
+    lists: usize,
+    /// `href`s of the links being read
+    links: Vec<String>,
+}
+
+impl Writer {
+    fn write(&mut self, token: Token) {
+        if let Some(pre) = &mut self.pre {
+            match token {
+                Token::Text(text) => pre.push_str(&decode_entities(text)),
+                Token::Close("pre") => self.end_pre(),
+                // Formatting inside examples is only highlighting
+                _ => {}
+            }
+            return;
+        }
+
+        match token {
+            Token::Text(text) => self.block.push_str(&decode_entities(text)),
+            Token::Open("pre", _) => self.pre = Some(String::new()),
+            Token::Open("code", _) => {
+                self.code += 1;
+                self.block.push('`');
+            }
+            Token::Close("code") => {
+                self.code = self.code.saturating_sub(1);
+                self.block.push('`');
+            }
+            Token::Open("em", _) | Token::Close("em") if self.code == 0 => self.block.push('*'),
+            Token::Open("a", attrs) => {
+                self.links.push(attribute(attrs, "href").unwrap_or_default().to_string());
+                self.block.push('[');
+            }
+            Token::Close("a") => {
+                let href = self.links.pop().unwrap_or_default();
+                self.block.push_str(&format!("]({href})"));
+            }
+            Token::Open("ul", _) => {
+                // A list nested in an item ends the item's own text
+                self.end_item();
+                self.lists += 1;
+            }
+            Token::Close("ul") => {
+                self.end_item();
+                self.lists = self.lists.saturating_sub(1);
+                if self.lists == 0 {
+                    self.markdown.push('\n');
+                }
+            }
+            Token::Close("li") => self.end_item(),
+            Token::Close("h2") => {
+                let text = self.take_block();
+                self.markdown.push_str(&format!("## {text}\n\n"));
+            }
+            Token::Close("p") => {
+                let text = self.take_block();
+                if !text.is_empty() {
+                    self.markdown.push_str(&format!("{text}\n\n"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The gathered inline text, with runs of whitespace collapsed.
+    fn take_block(&mut self) -> String {
+        let text = self.block.split_whitespace().collect::<Vec<_>>().join(" ");
+        self.block.clear();
+        text
+    }
+
+    fn end_item(&mut self) {
+        let text = self.take_block();
+        if !text.is_empty() {
+            let indent = "  ".repeat(self.lists.saturating_sub(1));
+            self.markdown.push_str(&format!("{indent}- {text}\n"));
+        }
+    }
+
+    fn end_pre(&mut self) {
+        let mut example = self.pre.take().unwrap_or_default();
+        if !example.ends_with('\n') {
+            example.push('\n');
+        }
+        self.markdown.push_str(&format!("```text\n{example}```\n\n"));
+        self.examples.push(example);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r#"<!DOCTYPE html>
+<html lang="en-us">
+<head><title>Day 1 - Advent of Code 2022</title></head>
+<body>
+<main>
+<article class="day-desc"><h2>--- Day 1: Calorie Counting ---</h2><p>The jungle must be too overgrown and difficult to navigate in vehicles or access from the air; the Elves' expedition traditionally goes on foot.</p>
+<p>For example, suppose the Elves finish writing their items' <em>Calories</em> into a list:</p>
+<pre><code>1000
+2000
+
+3000
+</code></pre>
+<p>This list represents the Calories of the food carried by <code>2</code> Elves:</p>
+<ul>
+<li>The first Elf is carrying food with <code>1000</code> and <code>2000</code> Calories, a total of <code><em>3000</em></code> Calories.</li>
+<li>The second Elf is carrying one food item with <code>3000</code> Calories.</li>
+</ul>
+<p>Find the Elf carrying the most Calories. <em>How many total Calories is that Elf carrying?</em></p>
+</article>
+<p>To begin, <a href="/2022/day/1/input" target="_blank">get your puzzle input</a>.</p>
+<!-- <article>not a puzzle</article> -->
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Stacks look like <code>[A] &amp; [B]</code> when &lt;drawn&gt;:</p>
+<pre><code>    [D]
+[N] [C]
+ 1   2</code></pre>
+<p>See <a href="/2022/day/5">the stacks</a>.</p>
+</article>
+</main>
+</body>
+</html>"#;
+
+    #[test]
+    fn from_html() {
+        let puzzle = Puzzle::from_html(HTML);
+        assert_eq!(puzzle.title, "Day 1: Calorie Counting");
+        assert_eq!(puzzle.examples, ["1000\n2000\n\n3000\n", "    [D]\n[N] [C]\n 1   2\n"]);
+
+        let expected = "\
+## --- Day 1: Calorie Counting ---
+
+The jungle must be too overgrown and difficult to navigate in vehicles or access from the air; the Elves' expedition traditionally goes on foot.
+
+For example, suppose the Elves finish writing their items' *Calories* into a list:
+
+```text
+1000
+2000
+
+3000
+```
+
+This list represents the Calories of the food carried by `2` Elves:
+
+- The first Elf is carrying food with `1000` and `2000` Calories, a total of `3000` Calories.
+- The second Elf is carrying one food item with `3000` Calories.
+
+Find the Elf carrying the most Calories. *How many total Calories is that Elf carrying?*
+
+## --- Part Two ---
+
+Stacks look like `[A] & [B]` when <drawn>:
+
+```text
+    [D]
+[N] [C]
+ 1   2
+```
+
+See [the stacks](/2022/day/5).
+";
+        assert_eq!(puzzle.markdown, expected);
+        assert!(puzzle.has_part_two());
+        assert!(!Puzzle::from_html(&HTML[..HTML.find("<article class=\"day-desc\"><h2 id").unwrap()]).has_part_two());
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!(decode_entities("a &lt;b&gt; &amp;&#39;&#x41; &unknown; & done"), "a <b> &'A &unknown; & done");
+    }
+
+    #[test]
+    fn write_examples() {
+        let dir = std::env::temp_dir().join("aoc_puzzle_examples");
+        let _ = fs::remove_dir_all(&dir);
+
+        let paths = Puzzle::from_html(HTML).write_examples(&dir).unwrap();
+        assert_eq!(paths, [dir.join("example_1.txt"), dir.join("example_2.txt")]);
+        assert_eq!(fs::read_to_string(&paths[0]).unwrap(), "1000\n2000\n\n3000\n");
+    }
+}
@@ -0,0 +1,359 @@
+//! The shape of a day's input, to check an input before solving with it. A
+//! truncated input or one pasted with extra whitespace otherwise fails deep
+//! inside a parser with a panic; checking it against the day's schema lists
+//! everything wrong with it, by line.
+//!
+//! ```
+//! use aoc::schema::{Line, Schema};
+//!
+//! // Groups of whole numbers, separated by blank lines
+//! let schema = Schema::blocks(Schema::lines(Line::parse::<u32>()));
+//!
+//! assert!(schema.check("1000\n2000\n\n3000\n").is_empty());
+//!
+//! let violations = schema.check("1000\n2000 \n\n\n3000\n");
+//! assert_eq!(violations.len(), 2);
+//! assert_eq!(violations[0].to_string(), "line 2, column 1: invalid \"2000 \": invalid digit found in string");
+//! assert_eq!(violations[1].to_string(), "line 4: unexpected blank line");
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{input, parse::located, ParseError};
+
+/// A line of the input with its number, counting from 1.
+pub type Numbered<'a> = (usize, &'a str);
+
+/// Something wrong with an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Line of the input, counting from 1, or `None` if it is about the
+    /// input as a whole
+    pub line: Option<usize>,
+    /// Character in the line, counting from 1, if known
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(line: Option<usize>, message: impl Into<String>) -> Self {
+        Self { line, column: None, message: message.into() }
+    }
+
+    /// A violation at `line`.
+    pub fn at(line: usize, message: impl Into<String>) -> Self {
+        Self::new(Some(line), message)
+    }
+
+    fn parse(line: usize, error: ParseError) -> Self {
+        Self { line: Some(line), column: Some(error.column), message: error.message }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {line}, column {column}: ")?,
+            (Some(line), None) => write!(f, "line {line}: ")?,
+            _ => {}
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Checks a line, saying where it goes wrong if it does.
+type LineCheck = dyn Fn(&str) -> Result<(), ParseError>;
+
+/// Checks all the lines at once, returning everything wrong with them.
+type LinesCheck = dyn Fn(&[Numbered]) -> Vec<Violation>;
+
+/// The grammar of one line.
+pub struct Line(Box<LineCheck>);
+
+impl Line {
+    /// Lines that `check` accepts. A `ParseError` it returns says where in
+    /// the line it went wrong; any other error is placed at its start.
+    pub fn new<E: Display + 'static>(check: impl Fn(&str) -> Result<(), E> + 'static) -> Self {
+        Self(Box::new(move |line| check(line).map_err(|e| located(e, 1, ToString::to_string))))
+    }
+
+    /// Lines that parse as a `T`, e.g. a type that derives `AocParse`.
+    pub fn parse<T>() -> Self
+    where
+        T: FromStr,
+        T::Err: Display + 'static,
+    {
+        Self::parse_with(|_: T| Ok(()))
+    }
+
+    /// Lines that parse as a `T` that `check` accepts, e.g. a number in
+    /// range. `check` says what is wrong with the value otherwise.
+    pub fn parse_with<T>(check: impl Fn(T) -> Result<(), String> + 'static) -> Self
+    where
+        T: FromStr,
+        T::Err: Display + 'static,
+    {
+        Self::new(move |line| {
+            let value = line.parse::<T>().map_err(|e| located(e, 1, |e| format!("invalid {line:?}: {e}")))?;
+            check(value).map_err(|message| ParseError::new(1, message))
+        })
+    }
+
+    /// Lines of one or more characters that `allowed` accepts, which are
+    /// described as `what`, e.g. "digits".
+    pub fn chars(what: &'static str, allowed: fn(char) -> bool) -> Self {
+        Self::new(move |line: &str| match line.chars().enumerate().find(|&(_, c)| !allowed(c)) {
+            Some((i, c)) => Err(ParseError::new(i + 1, format!("expected {what}, found {c:?}"))),
+            None => Ok(()),
+        })
+    }
+
+    fn check(&self, (number, text): Numbered, violations: &mut Vec<Violation>) {
+        if text.is_empty() {
+            violations.push(Violation::at(number, "unexpected blank line"));
+        } else if let Err(e) = (self.0)(text) {
+            violations.push(Violation::parse(number, e));
+        }
+    }
+}
+
+/// The shape of an input, or of part of one.
+pub enum Schema {
+    /// One or more lines, each following the grammar
+    Lines(Line),
+    /// Exactly these lines, in order
+    Exactly(Vec<Line>),
+    /// One or more rows of the same width, of characters that `allowed`
+    /// accepts, which are described as `cells`
+    Grid { cells: &'static str, allowed: fn(char) -> bool },
+    /// One or more groups of lines separated by single blank lines, each
+    /// following the schema
+    Blocks(Box<Schema>),
+    /// Groups of lines separated by single blank lines, following each
+    /// schema in turn
+    Sections(Vec<Schema>),
+    /// Following the schema, then passing a check of all the lines at once,
+    /// e.g. that references between them resolve. The check only runs if
+    /// the lines follow the schema, so it can parse them without failing.
+    Checked(Box<Schema>, Box<LinesCheck>),
+}
+
+impl Schema {
+    pub fn lines(line: Line) -> Self {
+        Self::Lines(line)
+    }
+
+    pub fn exactly(lines: impl IntoIterator<Item = Line>) -> Self {
+        Self::Exactly(lines.into_iter().collect())
+    }
+
+    pub fn grid(cells: &'static str, allowed: fn(char) -> bool) -> Self {
+        Self::Grid { cells, allowed }
+    }
+
+    pub fn blocks(block: Schema) -> Self {
+        Self::Blocks(Box::new(block))
+    }
+
+    pub fn sections(sections: impl IntoIterator<Item = Schema>) -> Self {
+        Self::Sections(sections.into_iter().collect())
+    }
+
+    /// Adds a check of all the lines at once. See [`Schema::Checked`].
+    pub fn with(self, check: impl Fn(&[Numbered]) -> Vec<Violation> + 'static) -> Self {
+        Self::Checked(Box::new(self), Box::new(check))
+    }
+
+    /// Everything wrong with `text`, in the order it appears. A byte order
+    /// mark and CRLF line endings are fine, as the days remove them when
+    /// they read their input (see [`input::normalize`]).
+    pub fn check(&self, text: &str) -> Vec<Violation> {
+        let (text, _) = input::normalize(text, input::Normalize::default());
+        if text.trim().is_empty() {
+            return vec![Violation::new(None, "input is empty")];
+        }
+        let lines = text.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .collect::<Vec<_>>();
+
+        let mut violations = vec![];
+        self.check_lines(&lines, &mut violations);
+        violations.sort_by_key(|violation| (violation.line.is_none(), violation.line, violation.column.is_none(), violation.column));
+        violations
+    }
+
+    fn check_lines(&self, lines: &[Numbered], violations: &mut Vec<Violation>) {
+        match self {
+            Self::Lines(line) => {
+                for &numbered in lines {
+                    line.check(numbered, violations);
+                }
+            }
+            Self::Exactly(expected) => {
+                for (&numbered, line) in lines.iter().zip(expected) {
+                    line.check(numbered, violations);
+                }
+                if lines.len() < expected.len() {
+                    let (last, _) = lines[lines.len() - 1];
+                    violations.push(Violation::at(last, format!(
+                        "ends after {} lines, where {} are expected", lines.len(), expected.len(),
+                    )));
+                } else if let Some(&(extra, _)) = lines.get(expected.len()) {
+                    violations.push(Violation::at(extra, format!(
+                        "unexpected line; expected {} lines here", expected.len(),
+                    )));
+                }
+            }
+            Self::Grid { cells, allowed } => {
+                let width = lines[0].1.chars().count();
+                let row = Line::chars(cells, *allowed);
+                for &(number, text) in lines {
+                    let before = violations.len();
+                    row.check((number, text), violations);
+                    let found = text.chars().count();
+                    if violations.len() == before && found != width {
+                        violations.push(Violation::at(number, format!(
+                            "row is {found} wide, where the first is {width}",
+                        )));
+                    }
+                }
+            }
+            Self::Blocks(block) => {
+                for group in groups(lines) {
+                    match group {
+                        Ok(lines) => block.check_lines(lines, violations),
+                        Err(blank) => violations.push(blank),
+                    }
+                }
+            }
+            Self::Sections(sections) => {
+                let groups = groups(lines);
+                for (group, section) in groups.iter().zip(sections) {
+                    match group {
+                        Ok(lines) => section.check_lines(lines, violations),
+                        Err(blank) => violations.push(blank.clone()),
+                    }
+                }
+                if groups.len() != sections.len() {
+                    violations.push(Violation::new(None, format!(
+                        "found {} sections separated by blank lines, where {} are expected",
+                        groups.len(), sections.len(),
+                    )));
+                }
+            }
+            Self::Checked(schema, check) => {
+                let before = violations.len();
+                schema.check_lines(lines, violations);
+                if violations.len() == before {
+                    violations.extend(check(lines));
+                }
+            }
+        }
+    }
+}
+
+/// Checks that `line` neither starts nor ends with whitespace, for grammars
+/// that would let it through, e.g. ones that split the line on whitespace.
+pub fn unpadded(line: &str) -> Result<(), ParseError> {
+    if line.starts_with(char::is_whitespace) {
+        Err(ParseError::new(1, "unexpected whitespace at the start"))
+    } else if line.ends_with(char::is_whitespace) {
+        Err(ParseError::new(line.trim_end().chars().count() + 1, "unexpected whitespace at the end"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Splits `lines` at blank lines. A group with no lines, from a blank line
+/// too many, is the violation at that blank line instead.
+fn groups<'a, 'b>(lines: &'a [Numbered<'b>]) -> Vec<Result<&'a [Numbered<'b>], Violation>> {
+    let mut groups = vec![];
+    let mut start = 0;
+    let mut blank = None;
+    for (i, &(number, text)) in lines.iter().enumerate() {
+        if text.is_empty() {
+            groups.push(group(&lines[start..i], number));
+            start = i + 1;
+            blank = Some(number);
+        }
+    }
+    if let Some(blank) = blank.filter(|_| start == lines.len()) {
+        groups.push(group(&[], blank));
+    } else {
+        groups.push(group(&lines[start..], 0));
+    }
+    groups
+}
+
+fn group<'a, 'b>(lines: &'a [Numbered<'b>], blank: usize) -> Result<&'a [Numbered<'b>], Violation> {
+    if lines.is_empty() {
+        Err(Violation::at(blank, "unexpected blank line"))
+    } else {
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AocParse;
+
+    #[derive(AocParse)]
+    #[aoc(format = "{0}-{1}")]
+    struct Range(u32, u32);
+
+    fn messages(violations: Vec<Violation>) -> Vec<String> {
+        violations.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn lines() {
+        let schema = Schema::lines(Line::parse_with(|Range(start, end)| {
+            if start <= end { Ok(()) } else { Err(format!("range ends at {end}, before its start {start}")) }
+        }));
+        assert!(schema.check("\u{feff}2-4\r\n6-8\r\n").is_empty());
+        assert_eq!(messages(schema.check("2-4\n6x8\n9-3\n1-\n")), [
+            "line 2, column 1: expected \"-\" after `0`, found \"6x8\"",
+            "line 3, column 1: range ends at 3, before its start 9",
+            "line 4, column 3: invalid `1` \"\": cannot parse integer from empty string",
+        ]);
+        assert_eq!(messages(schema.check(" \n")), ["input is empty"]);
+    }
+
+    #[test]
+    fn grids() {
+        let schema = Schema::grid("digits", |c| c.is_ascii_digit());
+        assert!(schema.check("303\n255\n").is_empty());
+        assert_eq!(messages(schema.check("303\n25\n2a5\n")), [
+            "line 2: row is 2 wide, where the first is 3",
+            "line 3, column 2: expected digits, found 'a'",
+        ]);
+    }
+
+    #[test]
+    fn blocks_and_sections() {
+        let block = || Schema::exactly([Line::parse::<char>(), Line::parse::<u8>()]);
+        let schema = Schema::blocks(block());
+        assert!(schema.check("a\n1\n\nb\n2\n").is_empty());
+        assert_eq!(messages(schema.check("a\n1\n\n\nb\n")), [
+            "line 4: unexpected blank line",
+            "line 5: ends after 1 lines, where 2 are expected",
+        ]);
+        assert_eq!(messages(schema.check("a\n1\nb\n\n")), [
+            "line 3: unexpected line; expected 2 lines here",
+            "line 4: unexpected blank line",
+        ]);
+
+        let schema = Schema::sections([block(), Schema::lines(Line::parse::<u8>())])
+            .with(|lines| {
+                let total = lines.iter().filter_map(|(_, line)| line.parse::<u32>().ok()).sum::<u32>();
+                if total > 10 { vec![Violation::new(None, format!("total is {total}"))] } else { vec![] }
+            });
+        assert!(schema.check("a\n1\n\n2\n3\n").is_empty());
+        assert_eq!(messages(schema.check("a\n1\n\n2\n9\n")), ["total is 12"]);
+        assert_eq!(messages(schema.check("a\n1\n")), [
+            "found 1 sections separated by blank lines, where 2 are expected",
+        ]);
+    }
+}
@@ -0,0 +1,260 @@
+//! Simulations that advance one tick at a time, like the rope of day 9, the
+//! CPU of day 10 and the monkeys of day 11, driven the same way whatever
+//! they simulate.
+//!
+//! A [`Simulation`] only knows how to take a step, whether it has finished
+//! and, optionally, how to draw itself. A [`Driver`] runs it: it stops at a
+//! step limit if there is one, calls observers after every tick, hands each
+//! drawing to whatever is making a visualization, and wraps each tick in a
+//! `tick` span so the simulation's own log events say which tick they are
+//! from.
+//!
+//! ```
+//! use aoc::simulation::{Driver, Simulation};
+//!
+//! /// Counts down to zero.
+//! struct Countdown(u32);
+//!
+//! impl Simulation for Countdown {
+//!     fn step(&mut self) {
+//!         self.0 -= 1;
+//!     }
+//!
+//!     fn done(&self) -> bool {
+//!         self.0 == 0
+//!     }
+//! }
+//!
+//! let mut seen = vec![];
+//! let outcome = Driver::new()
+//!     .observe(|_, countdown: &Countdown| seen.push(countdown.0))
+//!     .run(&mut Countdown(3));
+//! assert_eq!((outcome.ticks, outcome.done), (3, true));
+//! assert_eq!(seen, [2, 1, 0]);
+//!
+//! let outcome = Driver::new().limit(10).run(&mut Countdown(100));
+//! assert_eq!((outcome.ticks, outcome.done), (10, false));
+//! ```
+
+use std::hash::Hash;
+
+use crate::cycle::{self, History};
+
+/// Something that changes one tick at a time.
+pub trait Simulation {
+    /// Advances by one tick.
+    fn step(&mut self);
+
+    /// Whether there is nothing left to simulate. Simulations that could go
+    /// on forever never are, and are run with a [`Driver::limit`].
+    fn done(&self) -> bool {
+        false
+    }
+
+    /// A picture of the current state, for visualizations. `None` for
+    /// simulations that don't draw themselves.
+    fn render(&self) -> Option<Frame> {
+        None
+    }
+
+    /// Runs until done, with nothing watching.
+    fn run(&mut self) -> Outcome
+    where
+        Self: Sized,
+    {
+        Driver::new().run(self)
+    }
+
+    /// Steps a copy of the simulation until it repeats, or gives up after
+    /// `limit` ticks (see [`cycle::find`]). States are the same when their
+    /// `key`s are.
+    fn find_cycle<K: Eq + Hash>(&self, key: impl FnMut(&Self) -> K, limit: usize) -> Option<History<Self>>
+    where
+        Self: Clone + Sized,
+    {
+        let step = |state: &Self| {
+            let mut next = state.clone();
+            next.step();
+            next
+        };
+        cycle::find(self.clone(), step, key, limit)
+    }
+}
+
+/// A picture of a simulation: a grid of colors, each an index into whatever
+/// palette the visualization uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    /// Colors row by row, from the top left
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// A frame of color 0 all over.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, pixels: vec![0; width * height] }
+    }
+
+    /// The color at column `x` of row `y`, if that is in the frame.
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        (x < self.width).then(|| self.pixels.get(y * self.width + x).copied()).flatten()
+    }
+
+    /// Colors column `x` of row `y`, if that is in the frame.
+    pub fn set(&mut self, x: usize, y: usize, color: u8) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    /// The frame as text, one line per row, with color `i` drawn as
+    /// `palette[i]`.
+    pub fn to_text(&self, palette: &[char]) -> Vec<String> {
+        self.pixels.chunks(self.width.max(1))
+            .map(|row| row.iter().map(|&color| palette[usize::from(color)]).collect())
+            .collect()
+    }
+}
+
+/// How a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// Ticks taken
+    pub ticks: usize,
+    /// Whether the simulation finished, rather than reaching the limit
+    pub done: bool,
+}
+
+type Observer<'a, S> = Box<dyn FnMut(usize, &S) + 'a>;
+type FrameHandler<'a> = Box<dyn FnMut(usize, &Frame) + 'a>;
+
+/// Runs a [`Simulation`], with whatever is watching it.
+pub struct Driver<'a, S> {
+    limit: Option<usize>,
+    observers: Vec<Observer<'a, S>>,
+    frame_handlers: Vec<FrameHandler<'a>>,
+}
+
+impl<'a, S: Simulation> Driver<'a, S> {
+    pub fn new() -> Self {
+        Self { limit: None, observers: vec![], frame_handlers: vec![] }
+    }
+
+    /// Stops after `ticks` ticks, even if the simulation is not done.
+    pub fn limit(mut self, ticks: usize) -> Self {
+        self.limit = Some(ticks);
+        self
+    }
+
+    /// Calls `observer` after every tick with the number of ticks taken so
+    /// far and the simulation.
+    pub fn observe(mut self, observer: impl FnMut(usize, &S) + 'a) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Calls `handler` after every tick with the number of ticks taken so
+    /// far and the simulation's [`Simulation::render`]ing, if it has one.
+    /// Simulations are only rendered if there is a handler.
+    pub fn on_frame(mut self, handler: impl FnMut(usize, &Frame) + 'a) -> Self {
+        self.frame_handlers.push(Box::new(handler));
+        self
+    }
+
+    /// Steps `simulation` until it is done or the limit is reached. Without
+    /// a limit, a simulation that is never done runs forever.
+    pub fn run(mut self, simulation: &mut S) -> Outcome {
+        let mut ticks = 0;
+        loop {
+            if simulation.done() {
+                return Outcome { ticks, done: true };
+            }
+            if self.limit.is_some_and(|limit| ticks >= limit) {
+                return Outcome { ticks, done: false };
+            }
+
+            ticks += 1;
+            tracing::trace_span!("tick", tick = ticks).in_scope(|| simulation.step());
+
+            for observer in &mut self.observers {
+                observer(ticks, simulation);
+            }
+            if !self.frame_handlers.is_empty() {
+                if let Some(frame) = simulation.render() {
+                    for handler in &mut self.frame_handlers {
+                        handler(ticks, &frame);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<S: Simulation> Default for Driver<'_, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A light that goes round the edge of a 3 by 3 square, forever.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Light {
+        position: usize,
+        laps: usize,
+    }
+
+    impl Light {
+        const EDGE: [(usize, usize); 8] = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1)];
+    }
+
+    impl Simulation for Light {
+        fn step(&mut self) {
+            self.position = (self.position + 1) % Self::EDGE.len();
+            self.laps += usize::from(self.position == 0);
+        }
+
+        fn render(&self) -> Option<Frame> {
+            let mut frame = Frame::new(3, 3);
+            let (x, y) = Self::EDGE[self.position];
+            frame.set(x, y, 1);
+            Some(frame)
+        }
+    }
+
+    #[test]
+    fn drives_with_observers_and_frames() {
+        let mut light = Light { position: 0, laps: 0 };
+        let (mut positions, mut frames) = (vec![], vec![]);
+        let outcome = Driver::new()
+            .limit(9)
+            .observe(|tick, light: &Light| positions.push((tick, light.position)))
+            .on_frame(|_, frame| frames.push(frame.to_text(&['.', '#'])))
+            .run(&mut light);
+
+        assert_eq!(outcome, Outcome { ticks: 9, done: false });
+        assert_eq!(light, Light { position: 1, laps: 1 });
+        assert_eq!(positions[..3], [(1, 1), (2, 2), (3, 3)]);
+        assert_eq!(frames[2], ["...", "..#", "..."]);
+        assert_eq!(frames.len(), 9);
+
+        let frame = light.render().unwrap();
+        assert_eq!((frame.get(1, 0), frame.get(2, 0), frame.get(3, 0), frame.get(0, 3)), (Some(1), Some(0), None, None));
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let light = Light { position: 5, laps: 0 };
+        let history = light.find_cycle(|light| light.position, 100).unwrap();
+        assert_eq!((history.cycle.start, history.cycle.length), (0, 8));
+        // 3 laps and 2 steps on, the light is back at its start plus 2, but
+        // laps are not part of the key, so are counted as they first were
+        assert_eq!(history.state_at(26), &Light { position: 7, laps: 0 });
+        assert_eq!(history.total(26, |light| light.position), Some(3 * 28 + 5 + 6));
+    }
+}
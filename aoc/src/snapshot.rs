@@ -0,0 +1,165 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::input::{normalize, Normalize};
+
+/// Set this environment variable (to anything) to write the actual renderings
+/// into the snapshot files instead of comparing against them.
+pub const UPDATE_VAR: &str = "AOC_UPDATE_SNAPSHOTS";
+
+/// Asserts that `$actual`'s `Display` rendering matches the snapshot named
+/// `$name`, stored in a `snapshots` folder beside the calling source file. On
+/// a mismatch the test fails with a line diff; run the test again with
+/// `AOC_UPDATE_SNAPSHOTS=1` to accept the new rendering.
+///
+/// ```ignore
+/// aoc::assert_snapshot!("display", port);
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $actual:expr) => {
+        $crate::snapshot::assert_snapshot(
+            &$crate::snapshot::snapshot_path(env!("CARGO_MANIFEST_DIR"), file!(), $name),
+            &$actual.to_string(),
+        )
+    };
+}
+
+/// Where the snapshot `name` for a test in `file` is kept, e.g.
+/// `src/snapshots/port__display.snap` for a test in `src/port.rs`.
+///
+/// `file` is as given by `file!()`, which is relative to the workspace root
+/// rather than the crate, so the crate's folders are searched upwards for it.
+pub fn snapshot_path(manifest_dir: &str, file: &str, name: &str) -> PathBuf {
+    let source = Path::new(manifest_dir).ancestors()
+        .map(|dir| dir.join(file))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| Path::new(manifest_dir).join(file));
+
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    source.with_file_name("snapshots").join(format!("{stem}__{name}.snap"))
+}
+
+/// Compares `actual` with the snapshot at `path`, or overwrites the snapshot
+/// if `AOC_UPDATE_SNAPSHOTS` is set. Panics with a diff on mismatch.
+#[track_caller]
+pub fn assert_snapshot(path: &Path, actual: &str) {
+    let update = std::env::var_os(UPDATE_VAR).is_some();
+    if let Err(message) = check(path, actual, update) {
+        panic!("{message}");
+    }
+}
+
+fn check(path: &Path, actual: &str, update: bool) -> Result<(), String> {
+    if update {
+        return path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, actual))
+            .map_err(|e| format!("could not write snapshot {}: {e}", path.display()));
+    }
+
+    let expected = match fs::read_to_string(path) {
+        // A checkout may have given the file Windows line endings
+        Ok(expected) => normalize(&expected, Normalize::default()).0,
+        Err(e) => return Err(format!(
+            "could not read snapshot {}: {e}\nrun with {UPDATE_VAR}=1 to create it",
+            path.display(),
+        )),
+    };
+
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(format!(
+            "snapshot {} does not match (-expected +actual):\n{}run with {UPDATE_VAR}=1 to accept the new rendering",
+            path.display(),
+            diff(&expected, actual),
+        ))
+    }
+}
+
+/// A line diff of `expected` and `actual`: unchanged lines are prefixed with
+/// two spaces, removed lines with `- ` and added lines with `+ `.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let old = expected.lines().collect::<Vec<_>>();
+    let new = actual.lines().collect::<Vec<_>>();
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..]
+    // and new[j..]
+    let mut lcs = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut s = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            s.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            s.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            s.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("aoc_snapshot_tests");
+        fs::create_dir_all(&dir).unwrap();
+        let _ = fs::remove_file(dir.join(name));
+        dir.join(name)
+    }
+
+    #[test]
+    fn diff() {
+        let diff = super::diff("a\nb\nc\n", "a\nx\nc\nd\n");
+        assert_eq!(diff, "  a\n- b\n+ x\n  c\n+ d\n");
+    }
+
+    #[test]
+    fn update_then_match() {
+        let path = temp_path("nested/update_then_match.snap");
+        assert!(check(&path, "one\ntwo", false).unwrap_err().contains("AOC_UPDATE_SNAPSHOTS=1"));
+
+        check(&path, "one\ntwo", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo");
+        assert_eq!(check(&path, "one\ntwo", false), Ok(()));
+    }
+
+    #[test]
+    fn mismatch_shows_diff() {
+        let path = temp_path("mismatch_shows_diff.snap");
+        fs::write(&path, "one\r\ntwo\r\n").unwrap();
+
+        // Line endings alone are not a mismatch
+        assert_eq!(check(&path, "one\ntwo\n", false), Ok(()));
+
+        let message = check(&path, "one\nthree\n", false).unwrap_err();
+        assert!(message.contains("  one\n- two\n+ three\n"));
+    }
+
+    #[test]
+    fn path_beside_source() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let path = snapshot_path(manifest_dir, file!(), "diff");
+        assert_eq!(path, Path::new(manifest_dir).join("src/snapshots/snapshot__diff.snap"));
+    }
+}